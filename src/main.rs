@@ -1,17 +1,46 @@
 use crate::service::server::server_init::server_initializer;
+use crate::service::cli::commands::{USAGE, run_collect_once, run_read_point, run_validate};
+use crate::service::scan::modbus_scanner::{ScanConfig, run_scan_cli};
+use crate::service::simulate::{
+    modbus_simulator::{SimulatorConfig, run_simulator},
+    udp_generator::{UdpGeneratorConfig, run_udp_generator},
+    udp_replay::{UdpReplayConfig, run_udp_replay},
+};
 use crate::service::utils::setup_log_file::setup_log_file;
 use anyhow::{anyhow, Result};
 use tracing::info;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing_subscriber::{fmt, fmt::writer::BoxMakeWriter, prelude::*, EnvFilter};
 
 mod model {
     pub mod gems_3005 {
         pub mod data_models;
-        pub mod gems_3500_memory_map_models;
+    }
+
+    pub mod alarm {
+        pub mod alarm_models;
+    }
+
+    pub mod auth {
+        pub mod api_key_models;
+    }
+
+    pub mod quality {
+        pub mod quality_models;
+    }
+
+    pub mod rollup {
+        pub mod rollup_models;
+    }
+
+    pub mod virtual_point {
+        pub mod virtual_point_models;
     }
 
     pub mod modbus {
+        pub mod gateway_map_models;
+        pub mod memory_map_models;
         pub mod modbus_register_models;
+        pub mod write_models;
     }
     pub mod iaq {
         pub mod data_models;
@@ -24,30 +53,100 @@ mod model {
     pub mod gas {
         pub mod data_models;
     }
+
+    pub mod ccm {
+        pub mod data_models;
+    }
+
+    pub mod sihas {
+        pub mod data_models;
+    }
 }
 
 mod service {
+    pub mod alarm {
+        pub mod alarm_engine;
+    }
+
+    pub mod cache {
+        pub mod acquisition_timing;
+        pub mod backfill;
+        pub mod counter_deltas;
+        pub mod demand_tracker;
+        pub mod latest_values;
+        pub mod quality_tracker;
+        pub mod rollups;
+    }
+
+    pub mod capture {
+        pub mod frame_capture;
+    }
+
+    pub mod cli {
+        pub mod commands;
+    }
+
+    pub mod config {
+        pub mod validation;
+    }
+
     pub mod collect {
+        pub mod collection_plan;
         pub mod gas;
         pub mod gems_3500_modbus;
         pub mod heat;
+        pub mod virtual_points;
+    }
+
+    pub mod control {
+        pub mod modbus_write;
+    }
+
+    pub mod gateway {
+        pub mod modbus_gateway;
     }
 
     pub mod read {
+        pub mod diag_read;
         pub mod read_from_addr;
         pub mod read_from_register;
         pub mod interpret_modbus_register;
+        pub mod udp_handler;
 
         pub mod iaq {
+            pub mod frame_parser;
+            pub mod handler;
+            pub mod util_funcs;
+        }
+
+        pub mod ccm {
+            pub mod handler;
             pub mod util_funcs;
         }
+
+        pub mod sihas {
+            pub mod handler;
+            pub mod util_funcs;
+        }
+    }
+
+    pub mod scan {
+        pub mod modbus_scanner;
+        pub mod scan_jobs;
     }
 
     pub mod server {
+        pub mod alarm_handler;
+        pub mod auth;
+        pub mod status_handler;
+        pub mod capture_handler;
+        pub mod diag_handler;
         pub mod get_state;
         pub mod health_check;
+        pub mod scan_handler;
         pub mod server_init;
         pub mod udp_listener;
+        pub mod write_handler;
     }
 
     pub mod task {
@@ -55,9 +154,19 @@ mod service {
         pub mod task_init;
     }
 
+    pub mod simulate {
+        pub mod modbus_simulator;
+        pub mod udp_generator;
+        pub mod udp_replay;
+    }
+
     pub mod utils {
+        pub mod clock_health;
         pub mod create_time;
+        pub mod csv_file;
+        pub mod frame_log;
         pub mod setup_log_file;
+        pub mod xorshift;
     }
 }
 
@@ -77,10 +186,19 @@ async fn main() -> Result<()> {
     //     .with_env_filter(filter)
     //     .init();
 
-    // 콘솔 로그: info 이상
-    let stdout_layer = fmt::layer()
+    // 서브커맨드: serve(기본) / validate / collect-once / read, 오프라인 테스트 도구
+    let args: Vec<String> = std::env::args().collect();
+    let serving = matches!(args.get(1).map(String::as_str), None | Some("serve"));
+
+    // 콘솔 로그: info 이상. 서버는 stdout, CLI 서브커맨드는 stdout 의 JSON 출력과 섞이지 않도록 stderr
+    let console_writer = if serving {
+        BoxMakeWriter::new(std::io::stdout)
+    } else {
+        BoxMakeWriter::new(std::io::stderr)
+    };
+    let console_layer = fmt::layer()
         .with_ansi(false)
-        .with_writer(std::io::stdout) // stdout으로 info/warn/error
+        .with_writer(console_writer)
         .with_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")));
 
     // 파일 로그: error만
@@ -91,10 +209,24 @@ async fn main() -> Result<()> {
         .with_filter(EnvFilter::new("error"));
 
     tracing_subscriber::registry()
-        .with(stdout_layer)
+        .with(console_layer)
         .with(file_layer)
         .init();
 
+    match args.get(1).map(String::as_str) {
+        None | Some("serve") => {}
+        Some("validate") => return run_validate().await,
+        Some("collect-once") => return run_collect_once(&args[2..]).await,
+        Some("read") => return run_read_point(&args[2..]).await,
+        Some("simulate") => return run_simulator(SimulatorConfig::from_args(&args[2..])?).await,
+        Some("udp-send") => {
+            return run_udp_generator(UdpGeneratorConfig::from_args(&args[2..])?).await;
+        }
+        Some("udp-replay") => return run_udp_replay(UdpReplayConfig::from_args(&args[2..])?).await,
+        Some("scan") => return run_scan_cli(ScanConfig::from_args(&args[2..])?).await,
+        Some(other) => return Err(anyhow!("Unknown command {}\n{}", other, USAGE)),
+    }
+
     let result = server_initializer().await.map_err(|e| anyhow!("{:?}", e))?;
 
     info!("Server successfully terminated: {}", result);
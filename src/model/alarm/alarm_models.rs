@@ -0,0 +1,174 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::service::utils::csv_file::open_optional_csv;
+
+// 조건 종류
+//  above / below  : field 값이 threshold 초과 / 미만
+//  below_field    : field 가 other_field 보다 threshold 이상 낮음 (예: 공급온도 < 환수온도)
+//  imbalance_pct  : field 에 '|' 로 나열한 값들의 (최대-최소)/평균 × 100 이 threshold 초과
+//  offline        : threshold 주기 연속으로 레코드가 없거나 값이 모두 비어 있음
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmCondition {
+    Above,
+    Below,
+    BelowField,
+    ImbalancePct,
+    Offline,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AlarmRuleRow {
+    pub rule_id: String,
+    pub sensor_type: String,
+    pub iaq_type: Option<String>, // IAQ 는 포인트의 iaq_type 으로 한정 (field 는 value)
+    pub condition: String,
+    pub field: Option<String>,
+    pub other_field: Option<String>,
+    pub threshold: Option<f64>,
+    pub clear_threshold: Option<f64>, // 해제 기준 (히스테리시스). 비우면 threshold
+    pub for_minutes: Option<f64>,     // 조건이 이 시간 동안 계속돼야 발생
+    pub severity: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlarmRule {
+    pub row: AlarmRuleRow,
+    pub condition: AlarmCondition,
+    pub fields: Vec<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct AlarmRuleTable {
+    pub rules: Vec<AlarmRule>,
+}
+
+impl AlarmRuleTable {
+    pub fn from_csv() -> Result<AlarmRuleTable> {
+        // 파일이 없으면 알람 없음
+        let Some(mut rdr) = open_optional_csv("src/files/alarm_rules.csv")? else {
+            return Ok(Self::default());
+        };
+
+        let mut rules = Vec::new();
+        for result in rdr.deserialize() {
+            let row: AlarmRuleRow = result?;
+            let condition = match row.condition.as_str() {
+                "above" => AlarmCondition::Above,
+                "below" => AlarmCondition::Below,
+                "below_field" => AlarmCondition::BelowField,
+                "imbalance_pct" => AlarmCondition::ImbalancePct,
+                "offline" => AlarmCondition::Offline,
+                other => {
+                    return Err(anyhow!(
+                        "alarm_rules.csv: rule {} has unknown condition {}",
+                        row.rule_id,
+                        other
+                    ));
+                }
+            };
+
+            let fields: Vec<String> = row
+                .field
+                .as_deref()
+                .unwrap_or("")
+                .split('|')
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(str::to_owned)
+                .collect();
+            let missing = match condition {
+                AlarmCondition::Offline => None,
+                _ if fields.is_empty() => Some("field"),
+                AlarmCondition::BelowField if row.other_field.is_none() => Some("other_field"),
+                AlarmCondition::ImbalancePct if fields.len() < 2 => Some("field (2+ values)"),
+                _ if row.threshold.is_none() => Some("threshold"),
+                _ => None,
+            };
+            if let Some(missing) = missing {
+                return Err(anyhow!(
+                    "alarm_rules.csv: rule {} ({}) needs {}",
+                    row.rule_id,
+                    row.condition,
+                    missing
+                ));
+            }
+            if rules.iter().any(|r: &AlarmRule| r.row.rule_id == row.rule_id) {
+                return Err(anyhow!("alarm_rules.csv: duplicate rule_id {}", row.rule_id));
+            }
+
+            rules.push(AlarmRule {
+                row,
+                condition,
+                fields,
+            });
+        }
+
+        Ok(AlarmRuleTable { rules })
+    }
+
+    pub fn for_sensor(&self, sensor_type: &str) -> Vec<&AlarmRule> {
+        self.rules
+            .iter()
+            .filter(|r| r.row.sensor_type == sensor_type)
+            .collect()
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlarmStatus {
+    Raised,
+    Acknowledged,
+    Cleared,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Alarm {
+    pub id: Uuid,
+    pub rule_id: String,
+    pub sensor_type: String,
+    pub building_id: Uuid,
+    pub measurement_point_id: Uuid,
+    pub severity: String,
+    pub description: Option<String>,
+    pub status: AlarmStatus,
+    pub value: Option<f64>,
+    pub raised_at: DateTime<Utc>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub acknowledged_by: Option<String>, // api_keys.csv 의 operator
+    pub cleared_at: Option<DateTime<Utc>>,
+}
+
+// 웹훅으로 보내는 상태 변화
+#[derive(Serialize, Debug, Clone)]
+pub struct AlarmEvent {
+    pub event: AlarmStatus,
+    pub alarm: Alarm,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AlarmWebhook {
+    pub name: String,
+    pub url: String,
+}
+
+impl AlarmWebhook {
+    pub fn from_csv() -> Result<Vec<AlarmWebhook>> {
+        // 파일이 없으면 알림 없음
+        let Some(mut rdr) = open_optional_csv("src/files/alarm_webhooks.csv")? else {
+            return Ok(Vec::new());
+        };
+
+        let mut vec: Vec<AlarmWebhook> = Vec::new();
+        for result in rdr.deserialize() {
+            let record: AlarmWebhook = result?;
+            vec.push(record);
+        }
+
+        Ok(vec)
+    }
+}
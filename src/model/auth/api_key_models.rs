@@ -0,0 +1,67 @@
+use anyhow::{Result, anyhow};
+use serde_derive::Deserialize;
+
+use crate::service::utils::csv_file::open_optional_csv;
+
+// 제어 API 키 한 줄. 요청자는 본문이 아니라 키로 정해진다
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiKey {
+    pub operator: String,
+    pub key: String,
+}
+
+#[derive(Clone, Default)]
+pub struct ApiKeyTable {
+    pub rows: Vec<ApiKey>,
+}
+
+// 짧은 키는 추측 가능하므로 받지 않는다
+const MIN_KEY_LEN: usize = 16;
+
+impl ApiKeyTable {
+    pub fn from_csv() -> Result<ApiKeyTable> {
+        // 파일이 없으면 제어 API 는 모두 401
+        let Some(mut rdr) = open_optional_csv("src/files/api_keys.csv")? else {
+            return Ok(Self::default());
+        };
+
+        let mut rows: Vec<ApiKey> = Vec::new();
+        for result in rdr.deserialize() {
+            let record: ApiKey = result?;
+            if record.operator.trim().is_empty() {
+                return Err(anyhow!("api_keys.csv: operator is empty"));
+            }
+            if record.key.len() < MIN_KEY_LEN {
+                return Err(anyhow!(
+                    "api_keys.csv: key for {} is shorter than {} characters",
+                    record.operator,
+                    MIN_KEY_LEN
+                ));
+            }
+            if rows.iter().any(|r| r.key == record.key) {
+                return Err(anyhow!("api_keys.csv: duplicate key for {}", record.operator));
+            }
+            rows.push(record);
+        }
+
+        Ok(Self { rows })
+    }
+
+    // 키 → 요청자. 비교는 길이만 새는 상수 시간 비교로 모든 행에 대해 수행
+    pub fn operator_for(&self, key: &str) -> Option<&str> {
+        let mut found = None;
+        for row in &self.rows {
+            if constant_time_eq(row.key.as_bytes(), key.as_bytes()) {
+                found = Some(row.operator.as_str());
+            }
+        }
+        found
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::service::utils::csv_file::open_optional_csv;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CcmMeasurementPoint {
+    pub building_id: Uuid,
+    pub measurement_point_id: Uuid,
+    pub mac: String,
+}
+
+impl CcmMeasurementPoint {
+    pub fn from_csv() -> Result<Vec<CcmMeasurementPoint>> {
+        // 파일이 없으면 빈 벡터로 처리
+        let Some(mut rdr) = open_optional_csv("src/files/ccm.csv")? else {
+            return Ok(Vec::new());
+        };
+
+        let mut vec: Vec<CcmMeasurementPoint> = Vec::new();
+        for result in rdr.deserialize() {
+            let record: CcmMeasurementPoint = result?;
+            vec.push(record);
+        }
+
+        Ok(vec)
+    }
+}
+
+// 스마트콘센트 레지스터 디코딩 결과
+#[derive(Debug, Clone)]
+pub struct CcmData {
+    pub onoff: u16,
+    pub voltage: f64,
+    pub current: f64,
+    pub watt: f64,
+    pub power_factor: f64,
+    pub today_usage: f64,
+    pub this_month_usage: u32,
+}
+
+// api 호출시 전달값
+#[derive(Serialize, Debug, Clone)]
+pub struct CcmRecord {
+    pub building_id: Uuid,
+    pub measurement_point_id: Uuid,
+    pub on: Option<bool>,                // 전원 상태
+    pub voltage: Option<f64>,            // 전압 (V)
+    pub current: Option<f64>,            // 전류 (A)
+    pub watt: Option<f64>,               // 유효전력 (W)
+    pub power_factor: Option<f64>,       // 역률
+    pub today_usage: Option<f64>,        // 금일 사용량
+    pub this_month_usage: Option<f64>,   // 당월 사용량
+    pub recorded_at: DateTime<Utc>,
+    pub acquired_at: DateTime<Utc>,      // 패킷 수신 시각
+}
+
+impl CcmRecord {
+    pub fn new(
+        point: &CcmMeasurementPoint,
+        data: &CcmData,
+        recorded_at: DateTime<Utc>,
+        acquired_at: DateTime<Utc>,
+    ) -> Self {
+        CcmRecord {
+            building_id: point.building_id,
+            measurement_point_id: point.measurement_point_id,
+            on: Some(data.onoff != 0),
+            voltage: Some(data.voltage),
+            current: Some(data.current),
+            watt: Some(data.watt),
+            power_factor: Some(data.power_factor),
+            today_usage: Some(data.today_usage),
+            this_month_usage: Some(data.this_month_usage as f64),
+            recorded_at,
+            acquired_at,
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
-use std::{io, net::IpAddr};
+use std::net::IpAddr;
 use uuid::Uuid;
 
+use crate::model::gems_3005::data_models::DeltaFlag;
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct GasMeasurementPoint {
     pub building_id: Uuid,
@@ -13,33 +14,6 @@ pub struct GasMeasurementPoint {
     pub unit_id: u8,
 }
 
-impl GasMeasurementPoint {
-    pub fn from_csv() -> Result<Vec<GasMeasurementPoint>> {
-        let mut rdr = match csv::Reader::from_path("src/files/gas.csv") {
-            Ok(rdr) => rdr,
-            // 파일이 없으면 빈 벡터로 처리
-            Err(e) => {
-                if let csv::ErrorKind::Io(io_err) = e.kind() {
-                    if io_err.kind() == io::ErrorKind::NotFound {
-                        return Ok(Vec::new());
-                    }
-                }
-
-                return Err(e.into());
-            }
-        };
-
-        let mut vec: Vec<GasMeasurementPoint> = Vec::new();
-
-        for result in rdr.deserialize() {
-            let record: GasMeasurementPoint = result?;
-            vec.push(record);
-        }
-
-        Ok(vec)
-    }
-}
-
 // api 호출시 전달값
 #[derive(Serialize, Debug)]
 pub struct GasData {
@@ -49,5 +23,11 @@ pub struct GasData {
     pub instant_flow: Option<f64>,    // 순시유량
     pub pressure: Option<f64>,        // 압력
     pub temp: Option<f64>,            // 온도
-    pub recorded_at: DateTime<Utc>,
+    pub cumulative_flow_delta: Option<f64>, // 구간 유량
+    pub cumulative_flow_delta_flag: Option<DeltaFlag>,
+    pub recorded_at: DateTime<Utc>, // 예정 수집 슬롯 시각
+    pub acquired_at: DateTime<Utc>, // 실제로 읽은 시각
+    // 백필로 보간한 필드 (품질 플래그로 옮겨진 뒤 전송 본문에서는 빠진다)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interpolated: Vec<String>,
 }
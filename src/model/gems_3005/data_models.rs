@@ -1,8 +1,7 @@
-use std::io;
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
-use std::net::IpAddr;
+use std::{collections::BTreeMap, net::IpAddr};
 use uuid::Uuid;
 
 use crate::model::modbus::modbus_register_models::ModbusRegister;
@@ -11,6 +10,19 @@ pub const GEMS: &str = "gems";
 pub const IAQ: &str = "iaq";
 pub const HEAT: &str = "heat";
 pub const GAS: &str = "gas";
+pub const CCM: &str = "ccm";
+pub const VIRTUAL: &str = "virtual";
+
+// 적산값 구간 사용량 계산 결과
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeltaFlag {
+    Ok,       // 정상 증가
+    First,    // 이전 값 없음 (첫 수집)
+    Rollover, // 카운터 최대값을 넘어 0부터 다시 시작
+    Reset,    // 값이 줄어듦: 계량기 교체/리셋. 구간 사용량 모름
+    Jump,     // 경과 시간 대비 비정상적으로 큰 증가. 구간 사용량 버림
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RequestBody {
@@ -33,6 +45,23 @@ impl RequestBody {
             data: json_data,
         })
     }
+
+    // 레코드를 각자의 building_id 로 묶어 건물마다 본문 하나씩 만든다
+    pub fn per_building<T, F>(sensor_type: &str, data: Vec<T>, building_of: F) -> Result<Vec<Self>>
+    where
+        T: serde::Serialize,
+        F: Fn(&T) -> Uuid,
+    {
+        let mut groups: BTreeMap<Uuid, Vec<T>> = BTreeMap::new();
+        for record in data {
+            groups.entry(building_of(&record)).or_default().push(record);
+        }
+
+        groups
+            .into_iter()
+            .map(|(building_id, records)| Self::from_data(sensor_type, building_id, records))
+            .collect()
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -46,31 +75,7 @@ pub struct GemsMeasurementPoint {
     pub export_sum_status: bool,
 }
 
-impl GemsMeasurementPoint {
-    pub fn from_csv() -> Result<Vec<GemsMeasurementPoint>> {
-        let mut rdr = match csv::Reader::from_path("src/files/gems.csv") {
-            Ok(rdr) => rdr,
-            Err(e) => {
-                if let csv::ErrorKind::Io(io_err) = e.kind() {
-                    if io_err.kind() == io::ErrorKind::NotFound {
-                        return Ok(Vec::new());
-                    }
-                }
-
-                return Err(anyhow!("Failed to read csv file: {}", e))
-            }
-        };
-
-        let mut vec: Vec<GemsMeasurementPoint> = Vec::new();
-        for result in rdr.deserialize() {
-            let record: GemsMeasurementPoint = result?;
-            vec.push(record);
-        }
-
-        Ok(vec)
-    }
-}
-
+#[derive(Clone)]
 pub struct GemsCollectionSet {
     pub measurement_point_id: Uuid,
     pub building_id: Uuid,
@@ -86,7 +91,12 @@ impl GemsCollectionSet {
         }
     }
 
-    pub fn to_set_data(&self, values: GemsSetValue, recorded_at: DateTime<Utc>) -> GemsSetData {
+    pub fn to_set_data(
+        &self,
+        values: GemsSetValue,
+        recorded_at: DateTime<Utc>,
+        acquired_at: DateTime<Utc>,
+    ) -> GemsSetData {
         GemsSetData {
             building_id: self.building_id,
             measurement_point_id: self.measurement_point_id,
@@ -108,7 +118,26 @@ impl GemsCollectionSet {
             t_pf: values.t_pf,
             kwh_sum: values.kwh_sum,
             kwh_export_sum: values.kwh_export_sum,
+            kwh_sum_delta: None,
+            kwh_sum_delta_flag: None,
+            kwh_export_sum_delta: None,
+            kwh_export_sum_delta_flag: None,
+            r_va: None,
+            s_va: None,
+            t_va: None,
+            r_var: None,
+            s_var: None,
+            t_var: None,
+            total_va: None,
+            total_var: None,
+            voltage_unbalance_pct: None,
+            current_unbalance_pct: None,
+            demand_w: None,
+            peak_demand_w: None,
+            load_factor: None,
             recorded_at,
+            acquired_at,
+            interpolated: Vec::new(),
         }
     }
 }
@@ -135,7 +164,104 @@ pub struct GemsSetData {
     pub t_pf: Option<f64>,
     pub kwh_sum: Option<f64>,
     pub kwh_export_sum: Option<f64>,
-    pub recorded_at: DateTime<Utc>,
+    pub kwh_sum_delta: Option<f64>,
+    pub kwh_sum_delta_flag: Option<DeltaFlag>,
+    pub kwh_export_sum_delta: Option<f64>,
+    pub kwh_export_sum_delta_flag: Option<DeltaFlag>,
+    // 아래는 읽은 값에서 계산한 값 (입력이 없으면 None)
+    pub r_va: Option<f64>,
+    pub s_va: Option<f64>,
+    pub t_va: Option<f64>,
+    pub r_var: Option<f64>,
+    pub s_var: Option<f64>,
+    pub t_var: Option<f64>,
+    pub total_va: Option<f64>,
+    pub total_var: Option<f64>,
+    pub voltage_unbalance_pct: Option<f64>,
+    pub current_unbalance_pct: Option<f64>,
+    pub demand_w: Option<f64>,
+    pub peak_demand_w: Option<f64>,
+    pub load_factor: Option<f64>,
+    pub recorded_at: DateTime<Utc>, // 예정 수집 슬롯 시각
+    pub acquired_at: DateTime<Utc>, // 실제로 읽은 시각
+    // 백필로 보간한 필드 (품질 플래그로 옮겨진 뒤 전송 본문에서는 빠진다)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interpolated: Vec<String>,
+}
+
+impl GemsSetData {
+    // 상별 피상/무효전력, 3상 불평형률, 상별 값만 있을 때의 3상 합계
+    pub fn derive_metrics(&mut self) {
+        (self.r_va, self.r_var) = phase_power(self.r_v, self.r_a, self.r_w, self.r_pf);
+        (self.s_va, self.s_var) = phase_power(self.s_v, self.s_a, self.s_w, self.s_pf);
+        (self.t_va, self.t_var) = phase_power(self.t_v, self.t_a, self.t_w, self.t_pf);
+
+        self.voltage_unbalance_pct = unbalance_pct([self.r_v, self.s_v, self.t_v]);
+        self.current_unbalance_pct = unbalance_pct([self.r_a, self.s_a, self.t_a]);
+
+        let w = sum3([self.r_w, self.s_w, self.t_w]);
+        self.total_va = sum3([self.r_va, self.s_va, self.t_va]).map(round3);
+        // 부호 있는 상별 무효전력의 합 (진상/지상 상이 섞이면 서로 상쇄)
+        self.total_var = sum3([self.r_var, self.s_var, self.t_var]).map(round3);
+        if self.total_w.is_none() {
+            self.total_w = w;
+        }
+        // 3상 전류는 상 평균 (계량기 total_a 와 같은 의미)
+        if self.total_a.is_none() {
+            self.total_a = sum3([self.r_a, self.s_a, self.t_a]).map(|a| a / 3.0);
+        }
+        if self.total_pf.is_none()
+            && let (Some(w), Some(va)) = (self.total_w, self.total_va)
+            && va > 0.0
+        {
+            self.total_pf = Some(round3(w / va));
+        }
+    }
+}
+
+fn round3(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+// 피상전력 S = V·I, 무효전력 Q = √(S² − P²) (P 가 없으면 S·√(1 − pf²))
+// √ 로는 Q 의 부호를 알 수 없어 역률 부호를 따른다: pf < 0 (진상) 이면 음수.
+// 역률이 없으면 부호를 모르므로 양수로 둔다
+fn phase_power(
+    v: Option<f64>,
+    a: Option<f64>,
+    w: Option<f64>,
+    pf: Option<f64>,
+) -> (Option<f64>, Option<f64>) {
+    let Some(va) = v.zip(a).map(|(v, a)| v * a) else {
+        return (None, None);
+    };
+    let magnitude = match (w, pf) {
+        (Some(w), _) => Some((va * va - w * w).max(0.0).sqrt()),
+        (None, Some(pf)) => Some(va * (1.0 - pf * pf).max(0.0).sqrt()),
+        (None, None) => None,
+    };
+    let var = magnitude.map(|q| if pf.is_some_and(|pf| pf < 0.0) { -q } else { q });
+    (Some(round3(va)), var.map(round3))
+}
+
+// 평균 대비 최대 편차 (%)
+fn unbalance_pct(values: [Option<f64>; 3]) -> Option<f64> {
+    let [a, b, c] = values;
+    let (a, b, c) = (a?, b?, c?);
+    let mean = (a + b + c) / 3.0;
+    if mean == 0.0 {
+        return None;
+    }
+    let max_dev = [a, b, c]
+        .iter()
+        .map(|v| (v - mean).abs())
+        .fold(0.0, f64::max);
+    Some(round3(max_dev / mean * 100.0))
+}
+
+fn sum3(values: [Option<f64>; 3]) -> Option<f64> {
+    let [a, b, c] = values;
+    Some(a? + b? + c?)
 }
 
 pub struct GemsSetValue {
@@ -183,3 +309,150 @@ impl GemsSetValue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Rec {
+        building_id: Uuid,
+        measurement_point_id: Uuid,
+    }
+
+    #[test]
+    fn per_building_keeps_each_record_with_its_building() {
+        let (a, b, c) = (
+            Uuid::from_u128(0xA),
+            Uuid::from_u128(0xB),
+            Uuid::from_u128(0xC),
+        );
+        // 건물이 섞여 들어와도 (a, b, a, c, b) 각자의 본문으로만 간다
+        let owners = [a, b, a, c, b];
+        let records: Vec<Rec> = owners
+            .iter()
+            .enumerate()
+            .map(|(i, &building_id)| Rec {
+                building_id,
+                measurement_point_id: Uuid::from_u128(i as u128 + 1),
+            })
+            .collect();
+
+        let bodies = RequestBody::per_building(GEMS, records, |r| r.building_id).unwrap();
+        assert_eq!(bodies.len(), 3);
+
+        let mut seen = 0;
+        for body in &bodies {
+            assert_eq!(body.sensor_type, GEMS);
+            for record in body.data.as_array().unwrap() {
+                let mp: Uuid =
+                    serde_json::from_value(record["measurement_point_id"].clone()).unwrap();
+                let owner = owners[mp.as_u128() as usize - 1];
+                assert_eq!(
+                    body.building_id, owner,
+                    "point {} posted under wrong building",
+                    mp
+                );
+                assert_eq!(record["building_id"], serde_json::json!(owner));
+                seen += 1;
+            }
+        }
+        assert_eq!(seen, owners.len());
+
+        // 같은 건물 안에서는 입력 순서를 유지
+        let body_a = bodies.iter().find(|b| b.building_id == a).unwrap();
+        let ids: Vec<&str> = body_a
+            .data
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["measurement_point_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                Uuid::from_u128(1).to_string(),
+                Uuid::from_u128(3).to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn reactive_power_takes_the_sign_of_the_power_factor() {
+        // 220 V × 10 A = 2200 VA, 1760 W → |Q| = 1320 var
+        assert_eq!(
+            phase_power(Some(220.0), Some(10.0), Some(1760.0), Some(0.8)),
+            (Some(2200.0), Some(1320.0))
+        );
+        assert_eq!(
+            phase_power(Some(220.0), Some(10.0), Some(1760.0), Some(-0.8)),
+            (Some(2200.0), Some(-1320.0))
+        );
+        // W 없이 역률만 있을 때도 같은 부호
+        assert_eq!(
+            phase_power(Some(220.0), Some(10.0), None, Some(-0.8)),
+            (Some(2200.0), Some(-1320.0))
+        );
+        // 역률이 없으면 양수
+        assert_eq!(
+            phase_power(Some(220.0), Some(10.0), Some(1760.0), None),
+            (Some(2200.0), Some(1320.0))
+        );
+    }
+
+    #[test]
+    fn total_var_sums_signed_phases() {
+        let mut set = GemsSetData {
+            building_id: Uuid::nil(),
+            measurement_point_id: Uuid::nil(),
+            wire: None,
+            total_a: None,
+            total_w: None,
+            total_pf: None,
+            r_v: Some(220.0),
+            r_a: Some(10.0),
+            r_w: Some(1760.0),
+            r_pf: Some(0.8),
+            s_v: Some(220.0),
+            s_a: Some(10.0),
+            s_w: Some(1760.0),
+            s_pf: Some(-0.8),
+            t_v: Some(220.0),
+            t_a: Some(10.0),
+            t_w: Some(1760.0),
+            t_pf: Some(0.8),
+            kwh_sum: None,
+            kwh_export_sum: None,
+            kwh_sum_delta: None,
+            kwh_sum_delta_flag: None,
+            kwh_export_sum_delta: None,
+            kwh_export_sum_delta_flag: None,
+            r_va: None,
+            s_va: None,
+            t_va: None,
+            r_var: None,
+            s_var: None,
+            t_var: None,
+            total_va: None,
+            total_var: None,
+            voltage_unbalance_pct: None,
+            current_unbalance_pct: None,
+            demand_w: None,
+            peak_demand_w: None,
+            load_factor: None,
+            recorded_at: Utc::now(),
+            acquired_at: Utc::now(),
+            interpolated: Vec::new(),
+        };
+        set.derive_metrics();
+        assert_eq!(set.s_var, Some(-1320.0));
+        assert_eq!(set.total_var, Some(1320.0));
+        assert_eq!(set.total_va, Some(6600.0));
+    }
+
+    #[test]
+    fn per_building_of_nothing_is_no_bodies() {
+        let bodies = RequestBody::per_building(GEMS, Vec::<Rec>::new(), |r| r.building_id).unwrap();
+        assert!(bodies.is_empty());
+    }
+}
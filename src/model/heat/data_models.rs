@@ -1,9 +1,10 @@
-use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
-use std::{io, net::IpAddr};
+use std::net::IpAddr;
 use uuid::Uuid;
 
+use crate::model::gems_3005::data_models::DeltaFlag;
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct HeatMeasurementPoint {
     pub building_id: Uuid,
@@ -13,32 +14,6 @@ pub struct HeatMeasurementPoint {
     pub unit_id: u8,
 }
 
-impl HeatMeasurementPoint {
-    pub fn from_csv() -> Result<Vec<HeatMeasurementPoint>> {
-        let mut rdr = match csv::Reader::from_path("src/files/heat.csv") {
-            Ok(rdr) => rdr,
-            // 파일이 없으면 빈 벡터로 처리
-            Err(e) => {
-                if let csv::ErrorKind::Io(io_err) = e.kind() {
-                    if io_err.kind() == io::ErrorKind::NotFound {
-                        return Ok(Vec::new());
-                    }
-                }
-
-                return Err(e.into());
-            }
-        };
-
-        let mut vec: Vec<HeatMeasurementPoint> = Vec::new();
-        for result in rdr.deserialize() {
-            let record: HeatMeasurementPoint = result?;
-            vec.push(record);
-        }
-
-        Ok(vec)
-    }
-}
-
 // api 호출시 전달값
 #[derive(Serialize, Debug)]
 pub struct HeatData {
@@ -50,5 +25,13 @@ pub struct HeatData {
     pub return_temperature: Option<f64>, // 환수온도
     pub cumulative_flow: Option<f64>,    // 적산유량
     pub cumulative_heat: Option<f64>,    // 적산열량
-    pub recorded_at: DateTime<Utc>,
+    pub cumulative_flow_delta: Option<f64>, // 구간 유량
+    pub cumulative_flow_delta_flag: Option<DeltaFlag>,
+    pub cumulative_heat_delta: Option<f64>, // 구간 열량
+    pub cumulative_heat_delta_flag: Option<DeltaFlag>,
+    pub recorded_at: DateTime<Utc>, // 예정 수집 슬롯 시각
+    pub acquired_at: DateTime<Utc>, // 실제로 읽은 시각
+    // 백필로 보간한 필드 (품질 플래그로 옮겨진 뒤 전송 본문에서는 빠진다)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interpolated: Vec<String>,
 }
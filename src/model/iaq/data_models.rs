@@ -1,9 +1,17 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
+use dashmap::DashMap;
 use serde_derive::{Deserialize, Serialize};
-use std::{io, net::IpAddr};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    net::IpAddr,
+    sync::atomic::{AtomicU64, Ordering},
+};
 use uuid::Uuid;
 
+use crate::service::utils::csv_file::open_optional_csv;
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct IaqMeasurementPoint {
     pub building_id: Uuid,
@@ -16,18 +24,9 @@ pub struct IaqMeasurementPoint {
 
 impl IaqMeasurementPoint {
     pub fn from_csv() -> Result<Vec<IaqMeasurementPoint>> {
-        let mut rdr = match csv::Reader::from_path("src/files/iaq.csv") {
-            Ok(rdr) => rdr,
-            // 파일이 없으면 빈 벡터로 처리
-            Err(e) => {
-                if let csv::ErrorKind::Io(io_err) = e.kind() {
-                    if io_err.kind() == io::ErrorKind::NotFound {
-                        return Ok(Vec::new());
-                    }
-                }
-
-                return Err(e.into());
-            }
+        // 파일이 없으면 빈 벡터로 처리
+        let Some(mut rdr) = open_optional_csv("src/files/iaq.csv")? else {
+            return Ok(Vec::new());
         };
 
         let mut vec: Vec<IaqMeasurementPoint> = Vec::new();
@@ -40,6 +39,95 @@ impl IaqMeasurementPoint {
     }
 }
 
+// IAQ 레지스터 레이아웃 (버전별). version 0 은 기본 레이아웃.
+#[derive(Deserialize, Debug, Clone)]
+pub struct IaqRegisterMap {
+    pub version: u16,
+    pub register: usize,
+    pub bit: Option<u8>,
+    pub iaq_type: String,
+    pub unit: Option<String>,
+    pub data_type: String,
+    pub divide_by: f64,
+}
+
+pub const DEFAULT_IAQ_LAYOUT_VERSION: u16 = 0;
+
+#[derive(Clone)]
+pub struct IaqRegisterMapTable {
+    pub rows: Vec<IaqRegisterMap>,
+    pub idx_version: DashMap<u16, Vec<usize>>,
+}
+
+impl IaqRegisterMapTable {
+    pub fn from_csv() -> Result<IaqRegisterMapTable> {
+        Self::from_path("src/files/iaq_register_map.csv")
+    }
+
+    pub fn from_path(path: &str) -> Result<IaqRegisterMapTable> {
+        let mut rdr = csv::Reader::from_path(path)?;
+
+        let mut rows: Vec<IaqRegisterMap> = Vec::new();
+        for result in rdr.deserialize() {
+            let record: IaqRegisterMap = result?;
+            // 0 으로 나누면 inf/NaN 이 그대로 전송된다
+            if record.divide_by == 0.0 || !record.divide_by.is_finite() {
+                return Err(anyhow!(
+                    "{}: invalid divide_by {} for {} (version {}, register {})",
+                    path,
+                    record.divide_by,
+                    record.iaq_type,
+                    record.version,
+                    record.register
+                ));
+            }
+            rows.push(record);
+        }
+
+        let idx_version: DashMap<u16, Vec<usize>> = DashMap::new();
+        for (idx, row) in rows.iter().enumerate() {
+            idx_version.entry(row.version).or_default().push(idx);
+        }
+
+        if !idx_version.contains_key(&DEFAULT_IAQ_LAYOUT_VERSION) {
+            return Err(anyhow!(
+                "{} has no default layout (version {})",
+                path,
+                DEFAULT_IAQ_LAYOUT_VERSION
+            ));
+        }
+
+        Ok(Self { rows, idx_version })
+    }
+
+    // 프레임 version 에 맞는 레이아웃. 등록되지 않은 버전은 기본 레이아웃 사용
+    pub fn layout(&self, version: u16) -> Vec<&IaqRegisterMap> {
+        let idxs = self
+            .idx_version
+            .get(&version)
+            .or_else(|| self.idx_version.get(&DEFAULT_IAQ_LAYOUT_VERSION));
+
+        match idxs {
+            Some(idxs) => idxs.iter().map(|&i| &self.rows[i]).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // 어떤 레이아웃으로든 디코딩 가능한 iaq_type 전체
+    pub fn iaq_types(&self) -> BTreeSet<&str> {
+        self.rows.iter().map(|r| r.iaq_type.as_str()).collect()
+    }
+}
+
+// 디코딩된 IAQ 값 (단위 포함)
+#[derive(Debug, Clone)]
+pub struct IaqReading {
+    pub value: f64,
+    pub unit: Option<String>,
+}
+
+pub type IaqReadings = HashMap<String, IaqReading>;
+
 // UDP 패킷 헤더 구조체
 #[derive(Debug)]
 pub struct Header {
@@ -60,15 +148,106 @@ pub struct Message {
     pub checksum: u16,
 }
 
+// 파싱이 끝난 UDP 프레임
 #[derive(Debug)]
-pub struct CcmData {
-    pub onoff: u16,
-    pub voltage: f64,
-    pub current: f64,
-    pub watt: f64,
-    pub power_factor: f64,
-    pub today_usage: f64,
-    pub this_month_usage: u32,
+pub struct Frame {
+    pub header: Header,
+    pub function_code: u8,
+    pub local_addr: [u8; 6],
+    pub ssid: String,
+    pub mac: [u8; 6],
+    pub device_type: u8,
+    pub cfg: u8,
+    pub message: Message,
+}
+
+// 프레임 파싱 실패 사유
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    Truncated { needed: usize, available: usize },
+    LengthMismatch { declared: u16, actual: usize },
+    HeaderChecksum { expected: u8, actual: u8 },
+    BodyChecksum { expected: u16, actual: u16 },
+    FunctionCode(u8),
+    RegisterRange { offset: u16, count: u8 }, // offset + count 가 레지스터 주소 공간(0-65535)을 넘음
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Truncated { needed, available } => {
+                write!(f, "truncated frame: needed {} bytes, {} available", needed, available)
+            }
+            FrameError::LengthMismatch { declared, actual } => {
+                write!(f, "length mismatch: header declares {} bytes, got {}", declared, actual)
+            }
+            FrameError::HeaderChecksum { expected, actual } => {
+                write!(f, "bad header checksum: expected {:#04x}, got {:#04x}", expected, actual)
+            }
+            FrameError::BodyChecksum { expected, actual } => {
+                write!(f, "bad body checksum: expected {:#06x}, got {:#06x}", expected, actual)
+            }
+            FrameError::FunctionCode(code) => write!(f, "bad function code: {:#04x}", code),
+            FrameError::RegisterRange { offset, count } => {
+                write!(f, "register range overflow: offset {} + count {}", offset, count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+// 사유별 프레임 카운터 (수신 태스크 간 공유)
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    pub accepted: AtomicU64,
+    pub truncated: AtomicU64,
+    pub length_mismatch: AtomicU64,
+    pub header_checksum: AtomicU64,
+    pub body_checksum: AtomicU64,
+    pub function_code: AtomicU64,
+    pub register_range: AtomicU64,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct FrameStatsSnapshot {
+    pub accepted: u64,
+    pub truncated: u64,
+    pub length_mismatch: u64,
+    pub header_checksum: u64,
+    pub body_checksum: u64,
+    pub function_code: u64,
+    pub register_range: u64,
+}
+
+impl FrameStats {
+    pub fn record_accepted(&self) {
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_error(&self, err: &FrameError) {
+        let counter = match err {
+            FrameError::Truncated { .. } => &self.truncated,
+            FrameError::LengthMismatch { .. } => &self.length_mismatch,
+            FrameError::HeaderChecksum { .. } => &self.header_checksum,
+            FrameError::BodyChecksum { .. } => &self.body_checksum,
+            FrameError::FunctionCode(_) => &self.function_code,
+            FrameError::RegisterRange { .. } => &self.register_range,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> FrameStatsSnapshot {
+        FrameStatsSnapshot {
+            accepted: self.accepted.load(Ordering::Relaxed),
+            truncated: self.truncated.load(Ordering::Relaxed),
+            length_mismatch: self.length_mismatch.load(Ordering::Relaxed),
+            header_checksum: self.header_checksum.load(Ordering::Relaxed),
+            body_checksum: self.body_checksum.load(Ordering::Relaxed),
+            function_code: self.function_code.load(Ordering::Relaxed),
+            register_range: self.register_range.load(Ordering::Relaxed),
+        }
+    }
 }
 
 // api 호출시 전달값
@@ -77,5 +256,56 @@ pub struct IaqData {
     pub building_id: Uuid,
     pub measurement_point_id: Uuid,
     pub value: Option<f64>,
+    pub unit: Option<String>,
     pub recorded_at: DateTime<Utc>,
+    pub acquired_at: DateTime<Utc>, // 패킷 수신 시각
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, process};
+
+    fn load(name: &str, csv: &str) -> Result<IaqRegisterMapTable> {
+        let path = env::temp_dir().join(format!("iaq_map_{}_{}.csv", name, process::id()));
+        fs::write(&path, csv).unwrap();
+        let table = IaqRegisterMapTable::from_path(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+        table
+    }
+
+    const HEADER: &str = "version,register,bit,iaq_type,unit,data_type,divide_by\n";
+
+    #[test]
+    fn shipped_map_loads() {
+        let table = IaqRegisterMapTable::from_csv().unwrap();
+        assert_eq!(
+            table.iaq_types(),
+            BTreeSet::from([
+                "temperature",
+                "humidity",
+                "co2",
+                "pm25",
+                "pm10",
+                "tvoc",
+                "illuminance"
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_zero_divide_by() {
+        let csv = format!(
+            "{}0,0,,temperature,°C,INT16,10\n0,1,,humidity,%,UINT16,0\n",
+            HEADER
+        );
+        let err = load("zero", &csv).err().unwrap().to_string();
+        assert!(err.contains("divide_by"), "{}", err);
+    }
+
+    #[test]
+    fn requires_default_layout() {
+        let csv = format!("{}2,0,,temperature,°C,INT16,10\n", HEADER);
+        assert!(load("nodefault", &csv).is_err());
+    }
 }
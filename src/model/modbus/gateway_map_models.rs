@@ -0,0 +1,74 @@
+use anyhow::{Result, anyhow};
+use serde_derive::Deserialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::service::utils::csv_file::open_optional_csv;
+
+// 게이트웨이 레지스터 맵 한 행: 주소 ← (측정 포인트, 필드)
+#[derive(Deserialize, Debug, Clone)]
+pub struct GatewayRegisterMap {
+    pub address: u16,
+    pub measurement_point_id: Uuid,
+    pub field: String,
+    pub data_type: String, // FLOAT32 | INT16 | UINT16 | INT32 | UINT32
+    pub scale: Option<f64>, // 정수형일 때 곱할 값 (기본 1)
+}
+
+impl GatewayRegisterMap {
+    pub fn word_count(&self) -> u16 {
+        match self.data_type.as_str() {
+            "INT16" | "UINT16" => 1,
+            _ => 2,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct GatewayRegisterMapTable {
+    pub rows: Vec<GatewayRegisterMap>,
+    // 레지스터 주소 → (행 index, 행 안에서의 워드 위치)
+    pub idx_address: BTreeMap<u16, (usize, u16)>,
+}
+
+impl GatewayRegisterMapTable {
+    pub fn from_csv() -> Result<GatewayRegisterMapTable> {
+        // 파일이 없으면 게이트웨이 비활성
+        let Some(mut rdr) = open_optional_csv("src/files/modbus_gateway_map.csv")? else {
+            return Ok(Self::default());
+        };
+
+        let mut rows: Vec<GatewayRegisterMap> = Vec::new();
+        for result in rdr.deserialize() {
+            let record: GatewayRegisterMap = result?;
+            if !matches!(
+                record.data_type.as_str(),
+                "FLOAT32" | "INT16" | "UINT16" | "INT32" | "UINT32"
+            ) {
+                return Err(anyhow!(
+                    "modbus_gateway_map.csv: unknown data_type {} at address {}",
+                    record.data_type,
+                    record.address
+                ));
+            }
+            rows.push(record);
+        }
+
+        let mut idx_address = BTreeMap::new();
+        for (idx, row) in rows.iter().enumerate() {
+            for offset in 0..row.word_count() {
+                let addr = row.address.checked_add(offset).ok_or_else(|| {
+                    anyhow!("modbus_gateway_map.csv: address {} overflows", row.address)
+                })?;
+                if idx_address.insert(addr, (idx, offset)).is_some() {
+                    return Err(anyhow!(
+                        "modbus_gateway_map.csv: register {} is mapped twice",
+                        addr
+                    ));
+                }
+            }
+        }
+
+        Ok(Self { rows, idx_address })
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::{Result, anyhow};
+use serde_derive::Deserialize;
+use std::{collections::HashMap, fs::File, path::Path};
+
+use crate::model::modbus::modbus_register_models::ModbusRegister;
+
+// 장치 모델 이름 (memory_maps.csv 의 model, 쓰기 허용 목록의 profile 과 같은 이름)
+pub const GEMS_3500: &str = "gems_3500";
+pub const HEAT_METER: &str = "heat";
+pub const GAS_METER: &str = "gas";
+
+// 메모리 맵 한 행. 주소는 u16 (0-65535)
+#[derive(Clone, Debug, Deserialize)]
+pub struct MemoryMapRow {
+    #[serde(alias = "address")]
+    pub memory_address: u16,
+    pub data_category: Option<String>,
+    pub phase: Option<String>,
+    pub fc: Option<u8>,
+    pub data_type: Option<String>,
+    pub divide_by: Option<i16>,
+}
+
+// 장치 모델 하나의 메모리 맵. 주소 / (data_category, phase) 로 찾는다
+#[derive(Clone, Default)]
+pub struct MemoryMapTable {
+    pub model: String,
+    pub rows: Vec<MemoryMapRow>,
+    pub idx_memory_address: HashMap<u16, usize>,
+    pub idx_name: HashMap<(String, String), Vec<usize>>,
+}
+
+impl MemoryMapTable {
+    pub fn from_rows(model: &str, rows: Vec<MemoryMapRow>) -> Result<MemoryMapTable> {
+        let mut table = MemoryMapTable {
+            model: model.to_owned(),
+            ..Default::default()
+        };
+
+        for (idx, row) in rows.iter().enumerate() {
+            if table.idx_memory_address.insert(row.memory_address, idx).is_some() {
+                return Err(anyhow!(
+                    "{} memory map: duplicate address {}",
+                    model,
+                    row.memory_address
+                ));
+            }
+            if let Some(category) = &row.data_category {
+                table
+                    .idx_name
+                    .entry(name_key(category, row.phase.as_deref()))
+                    .or_default()
+                    .push(idx);
+            }
+        }
+        table.rows = rows;
+
+        Ok(table)
+    }
+
+    // 확장자로 CSV / JSON (행 배열) 구분
+    pub fn from_file(model: &str, path: &str) -> Result<MemoryMapTable> {
+        let rows: Vec<MemoryMapRow> =
+            if Path::new(path).extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) {
+                let file = File::open(path).map_err(|e| anyhow!("{}: {}", path, e))?;
+                serde_json::from_reader(file).map_err(|e| anyhow!("{}: {}", path, e))?
+            } else {
+                let mut rdr = csv::Reader::from_path(path).map_err(|e| anyhow!("{}: {}", path, e))?;
+                rdr.deserialize()
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| anyhow!("{}: {}", path, e))?
+            };
+
+        Self::from_rows(model, rows).map_err(|e| anyhow!("{}: {}", path, e))
+    }
+
+    pub fn get(&self, address: u16) -> Result<&MemoryMapRow> {
+        let idx = self.idx_memory_address.get(&address).ok_or_else(|| {
+            anyhow!(
+                "{} memory map misconfigured; reg_addr {} invalid. Aborting.",
+                self.model,
+                address
+            )
+        })?;
+
+        Ok(&self.rows[*idx])
+    }
+
+    // (data_category, phase) 가 같은 행들 (주소 순). 대소문자 구분 없음
+    pub fn by_name(&self, data_category: &str, phase: Option<&str>) -> Vec<&MemoryMapRow> {
+        self.idx_name
+            .get(&name_key(data_category, phase))
+            .map(|idx| idx.iter().map(|&i| &self.rows[i]).collect())
+            .unwrap_or_default()
+    }
+
+    // 이름으로 레지스터 하나 (값 필드 하나에 행 하나인 장치용: 열량계, 가스 유량계)
+    pub fn register(&self, data_category: &str) -> Result<ModbusRegister> {
+        match self.by_name(data_category, None).as_slice() {
+            [row] => Ok(ModbusRegister::from(*row)),
+            [] => Err(anyhow!("{} memory map has no {}", self.model, data_category)),
+            _ => Err(anyhow!("{} memory map has more than one {}", self.model, data_category)),
+        }
+    }
+}
+
+fn name_key(data_category: &str, phase: Option<&str>) -> (String, String) {
+    (
+        data_category.to_ascii_lowercase(),
+        phase.unwrap_or("").to_ascii_lowercase(),
+    )
+}
+
+#[derive(Deserialize)]
+struct MemoryMapSource {
+    model: String,
+    path: String,
+}
+
+// 장치 모델별 메모리 맵 (src/files/memory_maps.csv: model,path)
+#[derive(Clone, Default)]
+pub struct MemoryMapRegistry {
+    pub maps: HashMap<String, MemoryMapTable>,
+}
+
+impl MemoryMapRegistry {
+    pub fn from_csv() -> Result<MemoryMapRegistry> {
+        let mut rdr = csv::Reader::from_path("src/files/memory_maps.csv")?;
+
+        let mut maps = HashMap::new();
+        for result in rdr.deserialize() {
+            let source: MemoryMapSource = result?;
+            let table = MemoryMapTable::from_file(&source.model, &source.path)?;
+            if maps.insert(source.model.clone(), table).is_some() {
+                return Err(anyhow!("memory_maps.csv: duplicate model {}", source.model));
+            }
+        }
+
+        Ok(MemoryMapRegistry { maps })
+    }
+
+    pub fn get(&self, model: &str) -> Result<&MemoryMapTable> {
+        self.maps
+            .get(model)
+            .ok_or_else(|| anyhow!("No memory map for device model {}", model))
+    }
+}
@@ -1,33 +1,64 @@
-use crate::model::gems_3005::gems_3500_memory_map_models::Gems3500MemoryMap;
+use crate::model::modbus::memory_map_models::MemoryMapRow;
 use serde_derive::Serialize;
 
+// FLOAT32_WS     : IEEE-754, 하위 워드 먼저 (열량계)
+// DEC_PAIR       : 상위 워드 × 10000 + 하위 워드 (가스 유량계)
+// SIGN_MAGNITUDE : 첫 워드 0 이면 +, 아니면 −. 값은 두 번째 워드 (가스 유량계 온도)
+#[allow(non_camel_case_types)]
 #[derive(Serialize, Clone, Debug)]
 pub enum ModbusRegisterType {
     UINT16,
     UINT32,
     INT16,
     INT32,
+    FLOAT32_WS,
+    DEC_PAIR,
+    SIGN_MAGNITUDE,
     None,
 }
 
+impl ModbusRegisterType {
+    // 메모리 맵 data_type 이름 → 형식. 모르는 이름은 None
+    pub fn from_name(name: &str) -> Option<ModbusRegisterType> {
+        match name {
+            "INT16" => Some(ModbusRegisterType::INT16),
+            "INT32" => Some(ModbusRegisterType::INT32),
+            "UINT16" => Some(ModbusRegisterType::UINT16),
+            "UINT32" => Some(ModbusRegisterType::UINT32),
+            "FLOAT32_WS" => Some(ModbusRegisterType::FLOAT32_WS),
+            "DEC_PAIR" => Some(ModbusRegisterType::DEC_PAIR),
+            "SIGN_MAGNITUDE" => Some(ModbusRegisterType::SIGN_MAGNITUDE),
+            _ => None,
+        }
+    }
+
+    pub fn word_count(&self) -> u16 {
+        match self {
+            ModbusRegisterType::UINT16 | ModbusRegisterType::INT16 => 1,
+            ModbusRegisterType::None => 1,
+            _ => 2,
+        }
+    }
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct ModbusRegister {
     pub address: u16,
+    pub fc: u8, // 3 (holding) | 4 (input)
     pub value_type: ModbusRegisterType,
     pub divide_by: i16,
 }
 
-impl From<Gems3500MemoryMap> for ModbusRegister {
-    fn from(row: Gems3500MemoryMap) -> Self {
+impl From<&MemoryMapRow> for ModbusRegister {
+    fn from(row: &MemoryMapRow) -> Self {
         ModbusRegister {
-            address: row.memory_address as u16,
-            value_type: match row.data_type.as_deref() {
-                Some("INT16") => ModbusRegisterType::INT16,
-                Some("INT32") => ModbusRegisterType::INT32,
-                Some("UINT16") => ModbusRegisterType::UINT16,
-                Some("UINT32") => ModbusRegisterType::UINT32,
-                _ => ModbusRegisterType::None,
-            },
+            address: row.memory_address,
+            fc: row.fc.unwrap_or(4),
+            value_type: row
+                .data_type
+                .as_deref()
+                .and_then(ModbusRegisterType::from_name)
+                .unwrap_or(ModbusRegisterType::None),
             divide_by: row.divide_by.unwrap_or(1),
         }
     }
@@ -0,0 +1,108 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use std::net::IpAddr;
+use uuid::Uuid;
+
+use crate::service::utils::csv_file::open_optional_csv;
+
+// 장치 프로파일별 쓰기 허용 범위 [address, address + count).
+// profile 은 메모리 맵 모델 이름 (GEMS_3500 / HEAT_METER / GAS_METER)
+#[derive(Deserialize, Debug, Clone)]
+pub struct WriteAllowlistRow {
+    pub profile: String,
+    pub table: String, // holding | coil
+    pub address: u16,
+    pub count: u16,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct WriteAllowlist {
+    pub rows: Vec<WriteAllowlistRow>,
+}
+
+impl WriteAllowlist {
+    pub fn from_csv() -> Result<WriteAllowlist> {
+        // 파일이 없으면 아무 것도 쓸 수 없음
+        let Some(mut rdr) = open_optional_csv("src/files/modbus_write_allowlist.csv")? else {
+            return Ok(Self::default());
+        };
+
+        let mut rows: Vec<WriteAllowlistRow> = Vec::new();
+        for result in rdr.deserialize() {
+            let record: WriteAllowlistRow = result?;
+            if record.table != "holding" && record.table != "coil" {
+                return Err(anyhow!(
+                    "modbus_write_allowlist.csv: unknown table {} at address {}",
+                    record.table,
+                    record.address
+                ));
+            }
+            rows.push(record);
+        }
+
+        Ok(Self { rows })
+    }
+
+    // 요청 범위 전체가 한 허용 행 안에 들어가야 한다. 그 행을 돌려준다
+    pub fn allowing_row(
+        &self,
+        profile: &str,
+        table: &str,
+        address: u16,
+        count: u16,
+    ) -> Option<&WriteAllowlistRow> {
+        let end = address as u32 + count as u32;
+        self.rows.iter().find(|r| {
+            r.profile == profile
+                && r.table == table
+                && address >= r.address
+                && end <= r.address as u32 + r.count as u32
+        })
+    }
+}
+
+// 쓰기 대상 장치 (measurement_point_id 로 찾은 접속 정보)
+#[derive(Serialize, Debug, Clone)]
+pub struct WriteTarget {
+    pub measurement_point_id: Uuid,
+    pub profile: &'static str,
+    pub host: IpAddr,
+    pub port: u16,
+    pub unit_id: u8,
+}
+
+// POST /devices/{id}/write 본문
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WriteRequest {
+    pub table: String, // holding | coil
+    pub address: u16,
+    #[serde(default)]
+    pub values: Vec<u16>, // holding
+    #[serde(default)]
+    pub coils: Vec<bool>, // coil
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WriteResult {
+    pub target: WriteTarget,
+    pub allowed_by: Option<String>, // 허용한 행의 description
+    pub verified: bool,
+    pub read_back: Vec<u16>,
+}
+
+// 감사 로그 한 줄 (JSON lines, append-only)
+#[derive(Serialize, Debug, Clone)]
+pub struct WriteAuditEntry {
+    pub at: DateTime<Utc>,
+    pub operator: String, // api_keys.csv 의 operator (인증된 키 기준)
+    pub remote_addr: String,
+    pub device_id: String,
+    pub target: Option<WriteTarget>,
+    pub request: WriteRequest,
+    pub outcome: String, // written | verify_failed | rejected | failed
+    pub allowed_by: Option<String>,
+    pub read_back: Vec<u16>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,74 @@
+use anyhow::{Result, anyhow};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::service::utils::csv_file::open_optional_csv;
+
+// 필드별 품질 판정 결과
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityFlag {
+    Good,
+    Missing,      // 값을 읽지 못함
+    OutOfRange,   // min/max 밖
+    RateOfChange, // 직전 값 대비 분당 변화량 초과
+    Stuck,        // N 주기 연속 같은 값
+    Interpolated, // 수집이 빠진 슬롯을 앞뒤 적산값으로 보간해 채움
+}
+
+// sensor_type + 레코드 필드 이름별 검증 규칙. 비어 있는 칸은 검사하지 않는다
+#[derive(Deserialize, Debug, Clone)]
+pub struct QualityRule {
+    pub sensor_type: String,
+    pub field: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub max_change_per_minute: Option<f64>,
+    pub stuck_cycles: Option<u32>,
+}
+
+#[derive(Clone, Default)]
+pub struct QualityRuleTable {
+    pub rows: Vec<QualityRule>,
+    pub idx_sensor_type: HashMap<String, Vec<usize>>,
+}
+
+impl QualityRuleTable {
+    pub fn from_csv() -> Result<QualityRuleTable> {
+        // 파일이 없으면 검사하지 않음
+        let Some(mut rdr) = open_optional_csv("src/files/quality_rules.csv")? else {
+            return Ok(Self::default());
+        };
+
+        let mut table = QualityRuleTable::default();
+        for result in rdr.deserialize() {
+            let record: QualityRule = result?;
+            if let (Some(min), Some(max)) = (record.min, record.max)
+                && min > max
+            {
+                return Err(anyhow!(
+                    "quality_rules.csv: {}.{} has min {} > max {}",
+                    record.sensor_type,
+                    record.field,
+                    min,
+                    max
+                ));
+            }
+            table
+                .idx_sensor_type
+                .entry(record.sensor_type.clone())
+                .or_default()
+                .push(table.rows.len());
+            table.rows.push(record);
+        }
+
+        Ok(table)
+    }
+
+    pub fn rules(&self, sensor_type: &str) -> Vec<&QualityRule> {
+        self.idx_sensor_type
+            .get(sensor_type)
+            .map(|idx| idx.iter().map(|&i| &self.rows[i]).collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::{Result, anyhow};
+use serde_derive::Deserialize;
+
+use crate::service::utils::{
+    create_time::{DAY, HOUR, MINUTE, unit_minutes},
+    csv_file::open_optional_csv,
+};
+
+// 집계 구간 설정. name 이 롤업 본문의 sensor_type 이 된다
+//  window_minutes : 구간 길이 (분)
+//  align          : MINUTE / HOUR / DAY. 구간은 이 단위의 시작부터 나눈다 (길이는 단위를 나눠떨어져야 함)
+#[derive(Deserialize, Debug, Clone)]
+pub struct RollupWindow {
+    pub name: String,
+    pub sensor_type: String,
+    pub window_minutes: i64,
+    pub align: String,
+}
+
+#[derive(Clone, Default)]
+pub struct RollupWindowTable {
+    pub windows: Vec<RollupWindow>,
+}
+
+impl RollupWindowTable {
+    pub fn from_csv() -> Result<RollupWindowTable> {
+        // 파일이 없으면 집계하지 않음
+        let Some(mut rdr) = open_optional_csv("src/files/rollup_windows.csv")? else {
+            return Ok(Self::default());
+        };
+
+        let mut windows: Vec<RollupWindow> = Vec::new();
+        for result in rdr.deserialize() {
+            let window: RollupWindow = result?;
+            if ![MINUTE, HOUR, DAY].contains(&window.align.as_str()) {
+                return Err(anyhow!(
+                    "rollup_windows.csv: {} has unknown align {} (MINUTE, HOUR or DAY)",
+                    window.name,
+                    window.align
+                ));
+            }
+            let unit = unit_minutes(&window.align);
+            if window.window_minutes <= 0 || unit % window.window_minutes != 0 {
+                return Err(anyhow!(
+                    "rollup_windows.csv: {} window of {} minutes does not divide {} ({} minutes)",
+                    window.name,
+                    window.window_minutes,
+                    window.align,
+                    unit
+                ));
+            }
+            if windows.iter().any(|w| w.name == window.name) {
+                return Err(anyhow!("rollup_windows.csv: duplicate name {}", window.name));
+            }
+            windows.push(window);
+        }
+
+        Ok(RollupWindowTable { windows })
+    }
+
+    pub fn for_sensor(&self, sensor_type: &str) -> Vec<&RollupWindow> {
+        self.windows
+            .iter()
+            .filter(|w| w.sensor_type == sensor_type)
+            .collect()
+    }
+}
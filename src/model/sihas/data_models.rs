@@ -0,0 +1,115 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde_derive::{Deserialize, Serialize};
+use std::{collections::BTreeMap, net::IpAddr};
+use uuid::Uuid;
+
+pub const SIHAS: &str = "sihas";
+
+// MAC + field 로 측정 포인트 매핑. host/port 가 있으면 수집 주기마다 폴링 프레임을 보낸다.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SihasMeasurementPoint {
+    pub building_id: Uuid,
+    pub measurement_point_id: Uuid,
+    pub mac: String,
+    pub field: String,
+    pub host: Option<IpAddr>,
+    pub port: Option<u16>,
+}
+
+// SIHAS 장치 종류별 / 버전별 레지스터 레이아웃. version 0 은 기본 레이아웃.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SihasRegisterMap {
+    pub device_type: u8,
+    pub model: String,
+    pub version: u16,
+    pub register: usize,
+    pub bit: Option<u8>,
+    pub field: String,
+    pub unit: Option<String>,
+    pub data_type: String,
+    pub divide_by: f64,
+}
+
+#[derive(Clone)]
+pub struct SihasRegisterMapTable {
+    pub rows: Vec<SihasRegisterMap>,
+    pub idx_device_version: DashMap<(u8, u16), Vec<usize>>,
+}
+
+impl SihasRegisterMapTable {
+    pub fn from_csv() -> Result<SihasRegisterMapTable> {
+        Self::from_path("src/files/sihas_register_map.csv")
+    }
+
+    pub fn from_path(path: &str) -> Result<SihasRegisterMapTable> {
+        let mut rdr = csv::Reader::from_path(path)?;
+
+        let mut rows: Vec<SihasRegisterMap> = Vec::new();
+        for result in rdr.deserialize() {
+            let record: SihasRegisterMap = result?;
+            rows.push(record);
+        }
+
+        let idx_device_version: DashMap<(u8, u16), Vec<usize>> = DashMap::new();
+        for (idx, row) in rows.iter().enumerate() {
+            idx_device_version
+                .entry((row.device_type, row.version))
+                .or_default()
+                .push(idx);
+        }
+
+        for (device_type, _) in Self::models_of(&rows) {
+            if !idx_device_version.contains_key(&(device_type, 0)) {
+                return Err(anyhow!(
+                    "{} has no default layout (version 0) for device_type {}",
+                    path,
+                    device_type
+                ));
+            }
+        }
+
+        Ok(Self {
+            rows,
+            idx_device_version,
+        })
+    }
+
+    // 등록되지 않은 버전은 기본 레이아웃 사용
+    pub fn layout(&self, device_type: u8, version: u16) -> Vec<&SihasRegisterMap> {
+        let idxs = self
+            .idx_device_version
+            .get(&(device_type, version))
+            .or_else(|| self.idx_device_version.get(&(device_type, 0)));
+
+        match idxs {
+            Some(idxs) => idxs.iter().map(|&i| &self.rows[i]).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // device_type → 모델명
+    pub fn models(&self) -> BTreeMap<u8, String> {
+        Self::models_of(&self.rows)
+    }
+
+    fn models_of(rows: &[SihasRegisterMap]) -> BTreeMap<u8, String> {
+        rows.iter()
+            .map(|r| (r.device_type, r.model.clone()))
+            .collect()
+    }
+}
+
+// api 호출시 전달값
+#[derive(Serialize, Debug, Clone)]
+pub struct SihasData {
+    pub building_id: Uuid,
+    pub measurement_point_id: Uuid,
+    pub model: String,
+    pub field: String,
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+    pub acquired_at: DateTime<Utc>, // 패킷 수신 시각
+}
@@ -0,0 +1,279 @@
+use anyhow::{Result, anyhow};
+use serde_derive::Deserialize;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::service::utils::csv_file::open_optional_csv;
+
+// 산술식. 참조는 {measurement_point_id:field}
+//  예) {432ca26e-afb5-48c2-beb5-0dec5f87a501:total_w} - {a037969d-fb20-4dc7-a079-1e386de4a7ac:total_w}
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Number(f64),
+    Ref(Uuid, String),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn parse(src: &str) -> Result<Expr> {
+        let mut parser = Parser { src, pos: 0 };
+        let expr = parser.expr()?;
+        parser.skip_ws();
+        if parser.pos < src.len() {
+            return Err(anyhow!("unexpected '{}' at {}", &src[parser.pos..], parser.pos + 1));
+        }
+        Ok(expr)
+    }
+
+    // 입력이 하나라도 없거나 0 으로 나누면 None
+    pub fn eval<F>(&self, lookup: &F) -> Option<f64>
+    where
+        F: Fn(Uuid, &str) -> Option<f64>,
+    {
+        let v = match self {
+            Expr::Number(n) => *n,
+            Expr::Ref(mp, field) => lookup(*mp, field)?,
+            Expr::Neg(e) => -e.eval(lookup)?,
+            Expr::Add(a, b) => a.eval(lookup)? + b.eval(lookup)?,
+            Expr::Sub(a, b) => a.eval(lookup)? - b.eval(lookup)?,
+            Expr::Mul(a, b) => a.eval(lookup)? * b.eval(lookup)?,
+            Expr::Div(a, b) => {
+                let d = b.eval(lookup)?;
+                if d == 0.0 {
+                    return None;
+                }
+                a.eval(lookup)? / d
+            }
+        };
+        v.is_finite().then_some(v)
+    }
+
+    pub fn refs(&self, out: &mut Vec<(Uuid, String)>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Ref(mp, field) => out.push((*mp, field.clone())),
+            Expr::Neg(e) => e.refs(out),
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) | Expr::Div(a, b) => {
+                a.refs(out);
+                b.refs(out);
+            }
+        }
+    }
+}
+
+// expr := term (('+' | '-') term)* / term := factor (('*' | '/') factor)*
+// factor := '-' factor | number | '{' uuid ':' field '}' | '(' expr ')'
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_ws(&mut self) {
+        while let Some(c) = self.src[self.pos..].chars().next().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
+        self.src[self.pos..].chars().next()
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut lhs = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            lhs = match op {
+                '+' => Expr::Add(Box::new(lhs), Box::new(rhs)),
+                _ => Expr::Sub(Box::new(lhs), Box::new(rhs)),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr> {
+        let mut lhs = self.factor()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.factor()?;
+            lhs = match op {
+                '*' => Expr::Mul(Box::new(lhs), Box::new(rhs)),
+                _ => Expr::Div(Box::new(lhs), Box::new(rhs)),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn factor(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.factor()?)))
+            }
+            Some('(') => {
+                self.pos += 1;
+                let e = self.expr()?;
+                if self.peek() != Some(')') {
+                    return Err(anyhow!("missing ')' at {}", self.pos + 1));
+                }
+                self.pos += 1;
+                Ok(e)
+            }
+            Some('{') => {
+                let start = self.pos + 1;
+                let Some(len) = self.src[start..].find('}') else {
+                    return Err(anyhow!("missing '}}' after {}", start));
+                };
+                let inner = &self.src[start..start + len];
+                self.pos = start + len + 1;
+                let Some((mp, field)) = inner.split_once(':') else {
+                    return Err(anyhow!("reference {{{}}} must be {{measurement_point_id:field}}", inner));
+                };
+                let mp = Uuid::parse_str(mp.trim())
+                    .map_err(|e| anyhow!("bad measurement_point_id in {{{}}}: {}", inner, e))?;
+                Ok(Expr::Ref(mp, field.trim().to_owned()))
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos;
+                let len = self.src[start..]
+                    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                    .unwrap_or(self.src.len() - start);
+                self.pos = start + len;
+                let text = &self.src[start..self.pos];
+                text.parse()
+                    .map(Expr::Number)
+                    .map_err(|_| anyhow!("bad number {} at {}", text, start + 1))
+            }
+            Some(c) => Err(anyhow!("unexpected '{}' at {}", c, self.pos + 1)),
+            None => Err(anyhow!("unexpected end of expression")),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct VirtualPointRow {
+    pub building_id: Uuid,
+    pub measurement_point_id: Uuid,
+    pub field: String,
+    pub expression: String,
+    pub max_age_minutes: Option<i64>, // 입력값이 이보다 오래되면 없는 것으로 본다 (기본 10)
+}
+
+#[derive(Debug, Clone)]
+pub struct VirtualField {
+    pub field: String,
+    pub expr: Expr,
+    pub max_age_minutes: i64,
+}
+
+// 가상 포인트 하나 = 같은 measurement_point_id 의 행들 (필드별 식)
+#[derive(Debug, Clone)]
+pub struct VirtualPoint {
+    pub building_id: Uuid,
+    pub measurement_point_id: Uuid,
+    pub fields: Vec<VirtualField>,
+}
+
+#[derive(Clone, Default)]
+pub struct VirtualPointTable {
+    pub points: Vec<VirtualPoint>,
+}
+
+impl VirtualPointTable {
+    pub fn from_csv() -> Result<VirtualPointTable> {
+        // 파일이 없으면 가상 포인트 없음
+        let Some(mut rdr) = open_optional_csv("src/files/virtual_points.csv")? else {
+            return Ok(Self::default());
+        };
+
+        let mut table = VirtualPointTable::default();
+        for result in rdr.deserialize() {
+            let row: VirtualPointRow = result?;
+            let expr = Expr::parse(&row.expression).map_err(|e| {
+                anyhow!(
+                    "virtual_points.csv: {}.{}: {}",
+                    row.measurement_point_id,
+                    row.field,
+                    e
+                )
+            })?;
+            let field = VirtualField {
+                field: row.field,
+                expr,
+                max_age_minutes: row.max_age_minutes.unwrap_or(10),
+            };
+
+            match table
+                .points
+                .iter_mut()
+                .find(|p| p.measurement_point_id == row.measurement_point_id)
+            {
+                Some(point) if point.building_id != row.building_id => {
+                    return Err(anyhow!(
+                        "virtual_points.csv: {} has more than one building_id",
+                        row.measurement_point_id
+                    ));
+                }
+                Some(point) => point.fields.push(field),
+                None => table.points.push(VirtualPoint {
+                    building_id: row.building_id,
+                    measurement_point_id: row.measurement_point_id,
+                    fields: vec![field],
+                }),
+            }
+        }
+
+        Ok(table)
+    }
+
+    // 식이 참조하는 포인트는 실제 포인트이거나 앞에 정의된 가상 포인트(자기 자신의 앞 필드 포함)여야 한다
+    pub fn check_references(&self, real_points: &HashSet<Uuid>) -> Result<()> {
+        let mut known = real_points.clone();
+        for point in &self.points {
+            if real_points.contains(&point.measurement_point_id) {
+                return Err(anyhow!(
+                    "virtual_points.csv: {} is already a real measurement point",
+                    point.measurement_point_id
+                ));
+            }
+            known.insert(point.measurement_point_id);
+            for f in &point.fields {
+                let mut refs = Vec::new();
+                f.expr.refs(&mut refs);
+                if let Some((mp, _)) = refs.iter().find(|(mp, _)| !known.contains(mp)) {
+                    return Err(anyhow!(
+                        "virtual_points.csv: {}.{} refers to unknown measurement point {}",
+                        point.measurement_point_id,
+                        f.field,
+                        mp
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_around_non_ascii_whitespace() {
+        let mp = Uuid::from_u128(1);
+        // NBSP, 전각 공백, 탭
+        let src = format!("\u{a0}{{{}:total_w}}\u{3000}*\t2 ", mp);
+        let expr = Expr::parse(&src).unwrap();
+        let value = expr.eval(&|m, f| (m == mp && f == "total_w").then_some(1.5));
+        assert_eq!(value, Some(3.0));
+
+        assert!(Expr::parse("1 +\u{3000}").is_err());
+        assert!(Expr::parse("1 \u{3000}é").is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use reqwest::Client;
+use serde_json::{Map, Value};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    model::{
+        alarm::alarm_models::{
+            Alarm, AlarmCondition, AlarmEvent, AlarmRule, AlarmStatus, AlarmWebhook,
+        },
+        gems_3005::data_models::{GAS, GEMS, HEAT, IAQ, CCM, RequestBody, VIRTUAL},
+        sihas::data_models::SIHAS,
+    },
+    service::server::get_state::ServerState,
+};
+
+// 해제된 알람은 최근 것만 보관
+const HISTORY_LIMIT: usize = 500;
+
+#[derive(Default)]
+struct AlarmSlot {
+    pending_since: Option<DateTime<Utc>>,
+    misses: u32,
+    alarm: Option<Alarm>,
+}
+
+// (rule_id, measurement_point_id) 별 알람 상태
+#[derive(Default)]
+pub struct AlarmEngine {
+    slots: DashMap<(String, Uuid), AlarmSlot>,
+    history: Mutex<VecDeque<Alarm>>,
+}
+
+impl AlarmEngine {
+    // 발생/확인 상태 알람 (all 이면 최근 해제된 것 포함)
+    pub fn list(&self, all: bool) -> Vec<Alarm> {
+        let mut alarms: Vec<Alarm> = self
+            .slots
+            .iter()
+            .filter_map(|slot| slot.alarm.clone())
+            .collect();
+        if all {
+            let history = self.history.lock().expect("alarm history poisoned");
+            alarms.extend(history.iter().cloned());
+        }
+        alarms.sort_by_key(|a| std::cmp::Reverse(a.raised_at));
+        alarms
+    }
+
+    // 발생 상태 알람 확인 처리. 없거나 이미 확인했으면 None
+    pub fn acknowledge(&self, id: Uuid, operator: &str) -> Option<AlarmEvent> {
+        for mut slot in self.slots.iter_mut() {
+            let Some(alarm) = slot.alarm.as_mut() else {
+                continue;
+            };
+            if alarm.id != id || alarm.status != AlarmStatus::Raised {
+                continue;
+            }
+            alarm.status = AlarmStatus::Acknowledged;
+            alarm.acknowledged_at = Some(Utc::now());
+            alarm.acknowledged_by = Some(operator.to_owned());
+            return Some(AlarmEvent {
+                event: AlarmStatus::Acknowledged,
+                alarm: alarm.clone(),
+            });
+        }
+        None
+    }
+
+    // 한 수집 주기 결과로 규칙 평가. expected 는 이 센서의 설정된 (building_id, measurement_point_id)
+    pub fn evaluate_cycle(
+        &self,
+        rules: &[&AlarmRule],
+        sensor_type: &str,
+        expected: &[(Uuid, Uuid)],
+        bodies: &[RequestBody],
+        iaq_types: &HashMap<Uuid, String>,
+    ) -> Vec<AlarmEvent> {
+        let now = Utc::now();
+        let mut records: HashMap<Uuid, &Map<String, Value>> = HashMap::new();
+        for body in bodies {
+            if let Value::Array(items) = &body.data {
+                for obj in items.iter().filter_map(Value::as_object) {
+                    if let Some(mp) = obj
+                        .get("measurement_point_id")
+                        .and_then(Value::as_str)
+                        .and_then(|s| Uuid::parse_str(s).ok())
+                    {
+                        records.insert(mp, obj);
+                    }
+                }
+            }
+        }
+
+        let mut events = Vec::new();
+        for rule in rules {
+            for &(building_id, mp) in expected {
+                if let Some(t) = &rule.row.iaq_type
+                    && iaq_types.get(&mp) != Some(t)
+                {
+                    continue;
+                }
+
+                let record = records.get(&mp).copied();
+                let at = record
+                    .and_then(|o| o.get("recorded_at"))
+                    .and_then(Value::as_str)
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                    .map(|t| t.with_timezone(&Utc))
+                    .unwrap_or(now);
+
+                let key = (rule.row.rule_id.clone(), mp);
+                let mut slot = self.slots.entry(key).or_default();
+
+                let (active, clear, value) = if rule.condition == AlarmCondition::Offline {
+                    let online = record.is_some_and(has_values);
+                    slot.misses = if online { 0 } else { slot.misses + 1 };
+                    let cycles = rule.row.threshold.unwrap_or(3.0);
+                    (slot.misses as f64 >= cycles, online, Some(slot.misses as f64))
+                } else {
+                    let Some(metric) = record.and_then(|o| metric(rule, o)) else {
+                        continue;
+                    };
+                    let (raise, clear) = thresholds(rule, metric);
+                    (raise, clear, Some(metric))
+                };
+
+                events.extend(self.transition(
+                    &mut slot,
+                    rule,
+                    sensor_type,
+                    building_id,
+                    mp,
+                    at,
+                    (active, clear, value),
+                ));
+            }
+        }
+        events
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn transition(
+        &self,
+        slot: &mut AlarmSlot,
+        rule: &AlarmRule,
+        sensor_type: &str,
+        building_id: Uuid,
+        mp: Uuid,
+        at: DateTime<Utc>,
+        (active, clear, value): (bool, bool, Option<f64>),
+    ) -> Option<AlarmEvent> {
+        // 발생 중: 해제 기준을 만족할 때까지 유지 (히스테리시스)
+        if let Some(alarm) = slot.alarm.as_mut() {
+            if !clear {
+                alarm.value = value;
+                return None;
+            }
+            alarm.status = AlarmStatus::Cleared;
+            alarm.cleared_at = Some(at);
+            alarm.value = value;
+            let alarm = slot.alarm.take()?;
+            slot.pending_since = None;
+
+            let mut history = self.history.lock().expect("alarm history poisoned");
+            history.push_front(alarm.clone());
+            history.truncate(HISTORY_LIMIT);
+            return Some(AlarmEvent {
+                event: AlarmStatus::Cleared,
+                alarm,
+            });
+        }
+
+        if !active {
+            slot.pending_since = None;
+            return None;
+        }
+
+        let since = *slot.pending_since.get_or_insert(at);
+        let hold = Duration::seconds((rule.row.for_minutes.unwrap_or(0.0) * 60.0) as i64);
+        if at - since < hold {
+            return None;
+        }
+
+        let alarm = Alarm {
+            id: Uuid::new_v4(),
+            rule_id: rule.row.rule_id.clone(),
+            sensor_type: sensor_type.to_owned(),
+            building_id,
+            measurement_point_id: mp,
+            severity: rule.row.severity.clone(),
+            description: rule.row.description.clone(),
+            status: AlarmStatus::Raised,
+            value,
+            raised_at: at,
+            acknowledged_at: None,
+            acknowledged_by: None,
+            cleared_at: None,
+        };
+        slot.alarm = Some(alarm.clone());
+        Some(AlarmEvent {
+            event: AlarmStatus::Raised,
+            alarm,
+        })
+    }
+}
+
+// 측정값 필드가 하나라도 채워져 있으면 온라인
+fn has_values(obj: &Map<String, Value>) -> bool {
+    obj.iter().any(|(k, v)| {
+        v.is_number() && !k.ends_with("_delta") && k != "building_id" && k != "measurement_point_id"
+    })
+}
+
+fn field(obj: &Map<String, Value>, name: &str) -> Option<f64> {
+    obj.get(name).and_then(Value::as_f64)
+}
+
+// 규칙이 비교할 값
+fn metric(rule: &AlarmRule, obj: &Map<String, Value>) -> Option<f64> {
+    match rule.condition {
+        AlarmCondition::Above | AlarmCondition::Below => field(obj, &rule.fields[0]),
+        // 기준 필드보다 얼마나 낮은지
+        AlarmCondition::BelowField => {
+            let other = rule.row.other_field.as_deref()?;
+            Some(field(obj, other)? - field(obj, &rule.fields[0])?)
+        }
+        AlarmCondition::ImbalancePct => {
+            let values: Vec<f64> = rule
+                .fields
+                .iter()
+                .map(|f| field(obj, f))
+                .collect::<Option<_>>()?;
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            if mean == 0.0 {
+                return None;
+            }
+            let max = values.iter().cloned().fold(f64::MIN, f64::max);
+            let min = values.iter().cloned().fold(f64::MAX, f64::min);
+            Some((max - min) / mean * 100.0)
+        }
+        AlarmCondition::Offline => None,
+    }
+}
+
+// (발생 조건, 해제 조건)
+fn thresholds(rule: &AlarmRule, metric: f64) -> (bool, bool) {
+    let threshold = rule.row.threshold.unwrap_or(0.0);
+    let clear_at = rule.row.clear_threshold.unwrap_or(threshold);
+    match rule.condition {
+        AlarmCondition::Below => (metric < threshold, metric > clear_at),
+        _ => (metric > threshold, metric < clear_at),
+    }
+}
+
+// 센서 종류별로 설정된 (building_id, measurement_point_id)
+fn expected_points(state: &ServerState, sensor_type: &str) -> Vec<(Uuid, Uuid)> {
+    match sensor_type {
+        GEMS => state
+            .gems_measurement_point
+            .iter()
+            .map(|p| (p.building_id, p.measurement_point_id))
+            .collect(),
+        HEAT => state
+            .heat_measurement_point
+            .iter()
+            .map(|p| (p.building_id, p.measurement_point_id))
+            .collect(),
+        GAS => state
+            .gas_measurement_point
+            .iter()
+            .map(|p| (p.building_id, p.measurement_point_id))
+            .collect(),
+        IAQ => state
+            .iaq_measurement_point
+            .iter()
+            .map(|p| (p.building_id, p.measurement_point_id))
+            .collect(),
+        CCM => state
+            .ccm_measurement_point
+            .iter()
+            .map(|p| (p.building_id, p.measurement_point_id))
+            .collect(),
+        SIHAS => state
+            .sihas_measurement_point
+            .iter()
+            .map(|p| (p.building_id, p.measurement_point_id))
+            .collect(),
+        VIRTUAL => state
+            .virtual_point_table
+            .points
+            .iter()
+            .map(|p| (p.building_id, p.measurement_point_id))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// 수집 주기마다 호출: 규칙 평가 후 상태 변화는 웹훅으로 (백그라운드 전송)
+pub fn evaluate_alarms(state: &ServerState, sensor_type: &str, bodies: &[RequestBody]) {
+    let rules = state.alarm_rule_table.for_sensor(sensor_type);
+    if rules.is_empty() {
+        return;
+    }
+
+    let iaq_types: HashMap<Uuid, String> = if sensor_type == IAQ {
+        state
+            .iaq_measurement_point
+            .iter()
+            .map(|p| (p.measurement_point_id, p.iaq_type.clone()))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let expected = expected_points(state, sensor_type);
+    let events = state
+        .alarm_engine
+        .evaluate_cycle(&rules, sensor_type, &expected, bodies, &iaq_types);
+    notify(&state.alarm_webhooks, events);
+}
+
+pub fn notify(webhooks: &[AlarmWebhook], events: Vec<AlarmEvent>) {
+    for event in &events {
+        let a = &event.alarm;
+        warn!(
+            rule = %a.rule_id,
+            measurement_point_id = %a.measurement_point_id,
+            value = ?a.value,
+            "Alarm {:?} ({})",
+            event.event,
+            a.severity
+        );
+    }
+    if webhooks.is_empty() || events.is_empty() {
+        return;
+    }
+
+    let webhooks = webhooks.to_vec();
+    tokio::spawn(async move {
+        let client = Client::new();
+        for event in &events {
+            for hook in &webhooks {
+                match client
+                    .post(&hook.url)
+                    .timeout(std::time::Duration::from_secs(10))
+                    .json(event)
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                {
+                    Ok(_) => info!("Alarm event delivered to webhook {}", hook.name),
+                    Err(e) => error!("Could not deliver alarm event to webhook {}: {:?}", hook.name, e),
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,97 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde_derive::Serialize;
+use std::{collections::BTreeMap, env};
+use tracing::warn;
+use uuid::Uuid;
+
+// 슬롯이 끝난 뒤 이만큼(초)까지는 늦게 읽어도 경고하지 않는다
+const DEFAULT_TOLERANCE_SECONDS: i64 = 5;
+
+// 센서 종류별 마지막 주기의 예정 시각 대비 실제 수집 시각
+#[derive(Serialize, Debug, Clone)]
+pub struct SlotTiming {
+    pub slot: DateTime<Utc>,
+    pub slot_seconds: i64,
+    pub readings: usize,
+    pub max_lag_seconds: f64,   // 슬롯 시작 → 가장 늦은 수집
+    pub late_readings: usize,   // 슬롯 + 허용치를 넘긴 수집 (이번 주기)
+    pub total_late_readings: u64,
+}
+
+pub struct AcquisitionTiming {
+    tolerance_seconds: i64,
+    sensors: DashMap<String, SlotTiming>,
+}
+
+impl AcquisitionTiming {
+    // ACQUISITION_TOLERANCE_SECONDS (기본 5)
+    pub fn from_env() -> Result<AcquisitionTiming> {
+        let tolerance_seconds = match env::var("ACQUISITION_TOLERANCE_SECONDS") {
+            Ok(v) => v
+                .parse::<u32>()
+                .map_err(|_| anyhow!("ACQUISITION_TOLERANCE_SECONDS must be seconds, got {}", v))?
+                as i64,
+            Err(_) => DEFAULT_TOLERANCE_SECONDS,
+        };
+        Ok(AcquisitionTiming {
+            tolerance_seconds,
+            sensors: DashMap::new(),
+        })
+    }
+
+    // 한 주기의 (measurement_point_id, acquired_at) 를 슬롯과 비교. 다음 슬롯으로 넘어간 수집은 경고
+    pub fn check(
+        &self,
+        sensor_type: &str,
+        slot: DateTime<Utc>,
+        slot_seconds: i64,
+        readings: impl Iterator<Item = (Uuid, DateTime<Utc>)>,
+    ) {
+        let limit = slot_seconds + self.tolerance_seconds;
+        let mut timing = SlotTiming {
+            slot,
+            slot_seconds,
+            readings: 0,
+            max_lag_seconds: 0.0,
+            late_readings: 0,
+            total_late_readings: self
+                .sensors
+                .get(sensor_type)
+                .map(|t| t.total_late_readings)
+                .unwrap_or(0),
+        };
+
+        for (mp, acquired_at) in readings {
+            let lag = acquired_at - slot;
+            let lag_seconds = lag.num_milliseconds() as f64 / 1000.0;
+            timing.readings += 1;
+            timing.max_lag_seconds = timing.max_lag_seconds.max(lag_seconds);
+
+            if lag.num_seconds() >= limit {
+                timing.late_readings += 1;
+                timing.total_late_readings += 1;
+                warn!(
+                    measurement_point_id = %mp,
+                    slot = %slot.to_rfc3339(),
+                    acquired_at = %acquired_at.to_rfc3339(),
+                    "{} reading acquired {:.1}s after its slot started (slot is {}s, tolerance {}s)",
+                    sensor_type,
+                    lag_seconds,
+                    slot_seconds,
+                    self.tolerance_seconds
+                );
+            }
+        }
+
+        self.sensors.insert(sensor_type.to_owned(), timing);
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, SlotTiming> {
+        self.sensors
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect()
+    }
+}
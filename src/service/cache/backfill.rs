@@ -0,0 +1,61 @@
+use chrono::{DateTime, Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::service::cache::counter_deltas::CounterSample;
+
+// 이보다 긴 공백은 채우지 않는다 (구간 사용량은 Jump/Reset 판정에 맡김)
+const BACKFILL_MAX_HOURS: i64 = 24;
+
+// 직전 적산값 시각(prev_at) 다음 슬롯부터 at 직전 슬롯까지. 빠진 게 없거나 너무 길면 비어 있다
+pub fn missing_slots(
+    measurement_point_id: Uuid,
+    prev_at: DateTime<Utc>,
+    at: DateTime<Utc>,
+    slot_seconds: i64,
+) -> Vec<DateTime<Utc>> {
+    let step = Duration::seconds(slot_seconds);
+    if at - prev_at <= step {
+        return Vec::new();
+    }
+    if at - prev_at > Duration::hours(BACKFILL_MAX_HOURS) {
+        warn!(
+            measurement_point_id = %measurement_point_id,
+            "Gap since {} is longer than {}h; not backfilling",
+            prev_at.to_rfc3339(),
+            BACKFILL_MAX_HOURS
+        );
+        return Vec::new();
+    }
+
+    let mut slots = Vec::new();
+    let mut slot = prev_at + step;
+    while slot < at {
+        slots.push(slot);
+        slot += step;
+    }
+    info!(
+        measurement_point_id = %measurement_point_id,
+        "Backfilling {} missed slot(s) between {} and {}",
+        slots.len(),
+        prev_at.to_rfc3339(),
+        at.to_rfc3339()
+    );
+    slots
+}
+
+// 직전 적산값과 현재 값 사이 직선 보간. 값이 줄었으면(롤오버/리셋) 알 수 없으므로 None
+pub fn interpolate_counter(
+    prev: Option<CounterSample>,
+    value: Option<f64>,
+    at: DateTime<Utc>,
+    slot: DateTime<Utc>,
+) -> Option<f64> {
+    let (prev, value) = (prev?, value?);
+    if value < prev.value {
+        return None;
+    }
+    let span = (at - prev.recorded_at).num_seconds() as f64;
+    let ratio = (slot - prev.recorded_at).num_seconds() as f64 / span;
+    Some(((prev.value + (value - prev.value) * ratio) * 1e6).round() / 1e6)
+}
@@ -0,0 +1,303 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::Path,
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tokio::{sync::Mutex, task};
+use uuid::Uuid;
+
+use crate::model::gems_3005::data_models::DeltaFlag;
+
+const COUNTER_STATE_FILE: &str = "state/counters.json";
+
+// 적산 카운터 종류별 판정 기준
+#[derive(Debug, Clone, Copy)]
+pub struct CounterRule {
+    // 카운터가 되돌아가는 값 (UINT32 원시값 / divide_by). 없으면 감소는 모두 리셋
+    pub wrap: Option<f64>,
+    // 시간당 최대 정상 증가량. 넘으면 Jump
+    pub max_per_hour: f64,
+}
+
+// GEMS kWh: UINT32, divide_by 10 (메모리 맵 8000/9000 계열)
+pub const GEMS_KWH: CounterRule = CounterRule {
+    wrap: Some(4_294_967_296.0 / 10.0),
+    max_per_hour: 10_000.0,
+};
+// 열량계 적산값: FLOAT32 라 되돌아가지 않는다
+pub const HEAT_CUMULATIVE: CounterRule = CounterRule {
+    wrap: None,
+    max_per_hour: 10_000.0,
+};
+// 가스 적산유량 (m³)
+pub const GAS_CUMULATIVE: CounterRule = CounterRule {
+    wrap: None,
+    max_per_hour: 5_000.0,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct CounterSample {
+    pub value: f64,
+    pub recorded_at: DateTime<Utc>,
+    // 기준값 이후 Jump 로 버린 마지막 읽기 시각. 보간은 여기서부터 (그 슬롯은 이미 전송됨)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jumped_at: Option<DateTime<Utc>>,
+}
+
+impl CounterSample {
+    // 마지막으로 레코드를 보낸 슬롯
+    pub fn last_read_at(&self) -> DateTime<Utc> {
+        self.jumped_at.unwrap_or(self.recorded_at)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CounterEntry {
+    measurement_point_id: Uuid,
+    field: String,
+    #[serde(flatten)]
+    sample: CounterSample,
+}
+
+// 측정 포인트별 마지막 적산값. 재시작해도 구간 사용량이 이어지도록 파일에 저장한다.
+// GEMS/열량/가스 수집이 동시에 저장할 수 있어 저장은 save_lock 으로 한 번에 하나씩
+#[derive(Default)]
+pub struct CounterStore {
+    last: DashMap<(Uuid, String), CounterSample>,
+    save_lock: Mutex<()>,
+    save_seq: AtomicU64,
+}
+
+impl CounterStore {
+    // 파일이 없으면 빈 상태로 시작
+    pub fn load() -> Result<CounterStore> {
+        let text = match fs::read_to_string(COUNTER_STATE_FILE) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(anyhow!("Failed to read {}: {}", COUNTER_STATE_FILE, e)),
+        };
+
+        let entries: Vec<CounterEntry> = serde_json::from_str(&text)
+            .map_err(|e| anyhow!("Failed to parse {}: {}", COUNTER_STATE_FILE, e))?;
+        let last = DashMap::new();
+        for entry in entries {
+            last.insert((entry.measurement_point_id, entry.field), entry.sample);
+        }
+        Ok(CounterStore {
+            last,
+            ..Default::default()
+        })
+    }
+
+    // 임시 파일에 쓰고 rename (중간에 죽어도 이전 상태는 남는다).
+    // 스냅샷은 잠금을 잡은 뒤에 떠서 나중에 끝난 저장이 항상 최신 상태를 남긴다
+    pub async fn save(&self) -> Result<()> {
+        let _guard = self.save_lock.lock().await;
+        let entries: Vec<CounterEntry> = self
+            .last
+            .iter()
+            .map(|e| CounterEntry {
+                measurement_point_id: e.key().0,
+                field: e.key().1.clone(),
+                sample: *e.value(),
+            })
+            .collect();
+        let bytes = serde_json::to_vec(&entries)?;
+        let tmp = format!(
+            "{}.{}.{}.tmp",
+            COUNTER_STATE_FILE,
+            process::id(),
+            self.save_seq.fetch_add(1, Ordering::Relaxed)
+        );
+
+        task::spawn_blocking(move || -> Result<()> {
+            if let Some(dir) = Path::new(COUNTER_STATE_FILE).parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&tmp, bytes)?;
+            if let Err(e) = fs::rename(&tmp, COUNTER_STATE_FILE) {
+                let _ = fs::remove_file(&tmp);
+                return Err(e.into());
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| anyhow!("Counter save task failed: {}", e))?
+    }
+
+    pub fn last(&self, measurement_point_id: Uuid, field: &str) -> Option<CounterSample> {
+        self.last
+            .get(&(measurement_point_id, field.to_owned()))
+            .map(|s| *s)
+    }
+
+    // 새 적산값으로 직전 값 대비 구간 사용량 계산. 값이 없으면 (None, None)
+    // Jump 는 잘못 읽은 값일 수 있어 직전 값을 기준으로 그대로 둔다
+    pub fn observe(
+        &self,
+        measurement_point_id: Uuid,
+        field: &str,
+        value: Option<f64>,
+        recorded_at: DateTime<Utc>,
+        rule: CounterRule,
+    ) -> (Option<f64>, Option<DeltaFlag>) {
+        let Some(value) = value else {
+            return (None, None);
+        };
+
+        let key = (measurement_point_id, field.to_owned());
+        let previous = self.last.get(&key).map(|s| *s);
+        let result = counter_delta(previous, value, recorded_at, rule);
+        if result.1 == Some(DeltaFlag::Jump) {
+            if let Some(mut baseline) = self.last.get_mut(&key) {
+                baseline.jumped_at = Some(recorded_at);
+            }
+        } else {
+            self.last.insert(
+                key,
+                CounterSample {
+                    value,
+                    recorded_at,
+                    jumped_at: None,
+                },
+            );
+        }
+        result
+    }
+
+    // observe 와 같은 계산이지만 직전 값을 갱신하지 않는다 (collect-once)
+    pub fn preview(
+        &self,
+        measurement_point_id: Uuid,
+        field: &str,
+        value: Option<f64>,
+        recorded_at: DateTime<Utc>,
+        rule: CounterRule,
+    ) -> (Option<f64>, Option<DeltaFlag>) {
+        let Some(value) = value else {
+            return (None, None);
+        };
+        counter_delta(
+            self.last(measurement_point_id, field),
+            value,
+            recorded_at,
+            rule,
+        )
+    }
+}
+
+fn counter_delta(
+    previous: Option<CounterSample>,
+    value: f64,
+    recorded_at: DateTime<Utc>,
+    rule: CounterRule,
+) -> (Option<f64>, Option<DeltaFlag>) {
+    let Some(previous) = previous else {
+        return (None, Some(DeltaFlag::First));
+    };
+
+    // 최소 1분 구간으로 보고 허용 증가량 계산
+    let hours = ((recorded_at - previous.recorded_at).num_seconds().max(60)) as f64 / 3600.0;
+    let limit = rule.max_per_hour * hours;
+
+    let (delta, flag) = if value >= previous.value {
+        (value - previous.value, DeltaFlag::Ok)
+    } else {
+        match rule.wrap {
+            Some(wrap) if wrap - previous.value + value <= limit => {
+                (wrap - previous.value + value, DeltaFlag::Rollover)
+            }
+            _ => return (None, Some(DeltaFlag::Reset)),
+        }
+    };
+
+    if delta > limit {
+        return (None, Some(DeltaFlag::Jump));
+    }
+    // 부동소수 뺄셈 오차 정리
+    (Some((delta * 1e6).round() / 1e6), Some(flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    const RULE: CounterRule = CounterRule {
+        wrap: Some(1000.0),
+        max_per_hour: 100.0,
+    };
+
+    #[test]
+    fn preview_does_not_move_the_baseline() {
+        let store = CounterStore::default();
+        let mp = Uuid::from_u128(1);
+        let t0 = Utc::now();
+        store.observe(mp, "kwh_sum", Some(10.0), t0, RULE);
+
+        let t1 = t0 + Duration::minutes(5);
+        assert_eq!(
+            store.preview(mp, "kwh_sum", Some(12.5), t1, RULE),
+            (Some(2.5), Some(DeltaFlag::Ok))
+        );
+        assert_eq!(store.last(mp, "kwh_sum").unwrap().value, 10.0);
+        assert!(
+            store
+                .preview(Uuid::from_u128(2), "kwh_sum", Some(1.0), t1, RULE)
+                .1
+                == Some(DeltaFlag::First)
+        );
+        assert!(store.last(Uuid::from_u128(2), "kwh_sum").is_none());
+    }
+
+    #[test]
+    fn rollover_and_reset() {
+        let store = CounterStore::default();
+        let mp = Uuid::from_u128(1);
+        let t0 = Utc::now();
+        assert_eq!(
+            store.observe(mp, "kwh_sum", Some(995.0), t0, RULE),
+            (None, Some(DeltaFlag::First))
+        );
+        let t1 = t0 + Duration::hours(1);
+        assert_eq!(
+            store.observe(mp, "kwh_sum", Some(5.0), t1, RULE),
+            (Some(10.0), Some(DeltaFlag::Rollover))
+        );
+        let t2 = t1 + Duration::hours(1);
+        assert_eq!(
+            store.observe(mp, "kwh_sum", Some(1.0), t2, RULE),
+            (None, Some(DeltaFlag::Reset))
+        );
+    }
+
+    #[test]
+    fn jump_keeps_the_previous_baseline() {
+        let store = CounterStore::default();
+        let mp = Uuid::from_u128(1);
+        let t0 = Utc::now();
+        store.observe(mp, "kwh_sum", Some(100.0), t0, RULE);
+
+        // 1분에 500 증가: 잘못 읽은 값
+        let t1 = t0 + Duration::minutes(1);
+        assert_eq!(
+            store.observe(mp, "kwh_sum", Some(600.0), t1, RULE),
+            (None, Some(DeltaFlag::Jump))
+        );
+        let baseline = store.last(mp, "kwh_sum").unwrap();
+        assert_eq!((baseline.value, baseline.recorded_at), (100.0, t0));
+        assert_eq!(baseline.last_read_at(), t1);
+
+        // 다음 정상 값은 마지막 정상 값 기준으로 계산
+        let t2 = t1 + Duration::minutes(1);
+        assert_eq!(
+            store.observe(mp, "kwh_sum", Some(101.0), t2, RULE),
+            (Some(1.0), Some(DeltaFlag::Ok))
+        );
+    }
+}
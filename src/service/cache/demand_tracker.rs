@@ -0,0 +1,68 @@
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+use crate::model::gems_3005::data_models::GemsSetData;
+
+// 수요전력: 최근 15분 평균 유효전력
+const DEMAND_MINUTES: i64 = 15;
+// 최대수요/부하율 기준 구간
+const PEAK_HOURS: i64 = 24;
+
+#[derive(Default)]
+struct DemandHistory {
+    power: VecDeque<(DateTime<Utc>, f64)>,  // total_w 샘플 (PEAK_HOURS)
+    demand: VecDeque<(DateTime<Utc>, f64)>, // 매 주기 계산한 수요전력 (PEAK_HOURS)
+}
+
+// measurement_point_id 별 유효전력 이력. 메모리에만 있어 재시작하면 처음부터 다시 쌓인다
+#[derive(Default)]
+pub struct DemandTracker {
+    points: DashMap<Uuid, DemandHistory>,
+}
+
+impl DemandTracker {
+    // demand_w / peak_demand_w / load_factor 를 채운다. total_w 가 없으면 건너뜀
+    pub fn apply(&self, records: &mut [GemsSetData]) {
+        for r in records.iter_mut() {
+            let Some(w) = r.total_w else {
+                continue;
+            };
+            let at = r.recorded_at;
+            let mut h = self.points.entry(r.measurement_point_id).or_default();
+
+            h.power.push_back((at, w));
+            let since = at - Duration::hours(PEAK_HOURS);
+            while h.power.front().is_some_and(|(t, _)| *t <= since) {
+                h.power.pop_front();
+            }
+
+            let demand_since = at - Duration::minutes(DEMAND_MINUTES);
+            let recent: Vec<f64> = h
+                .power
+                .iter()
+                .filter(|(t, _)| *t > demand_since)
+                .map(|(_, w)| *w)
+                .collect();
+            let demand = recent.iter().sum::<f64>() / recent.len() as f64;
+
+            h.demand.push_back((at, demand));
+            while h.demand.front().is_some_and(|(t, _)| *t <= since) {
+                h.demand.pop_front();
+            }
+            let peak = h.demand.iter().map(|(_, d)| *d).fold(f64::MIN, f64::max);
+
+            // 부하율 = 평균 전력 / 최대수요
+            let mean = h.power.iter().map(|(_, w)| *w).sum::<f64>() / h.power.len() as f64;
+
+            r.demand_w = Some(round3(demand));
+            r.peak_demand_w = Some(round3(peak));
+            r.load_factor = (peak > 0.0).then(|| round3(mean / peak));
+        }
+    }
+}
+
+fn round3(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde_derive::Serialize;
+use serde_json::Value;
+use tracing::error;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LatestValue {
+    pub value: f64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+// 측정 포인트별 최신값 캐시 (measurement_point_id, field) → 값.
+// 수집기가 만든 레코드를 그대로 받아 숫자/불리언 필드를 모두 담는다.
+#[derive(Default)]
+pub struct LatestValueCache {
+    values: DashMap<(Uuid, String), LatestValue>,
+}
+
+impl LatestValueCache {
+    pub fn get(&self, measurement_point_id: Uuid, field: &str) -> Option<LatestValue> {
+        self.values
+            .get(&(measurement_point_id, field.to_string()))
+            .map(|v| *v)
+    }
+
+    pub fn update_from_records<T: serde::Serialize>(&self, records: &[T]) {
+        for record in records {
+            match serde_json::to_value(record) {
+                Ok(value) => self.update_from_value(&value),
+                Err(e) => error!("Could not cache record: {:?}", e),
+            }
+        }
+    }
+
+    pub fn update_from_value(&self, record: &Value) {
+        let Some(obj) = record.as_object() else {
+            return;
+        };
+        let Some(mp_id) = obj
+            .get("measurement_point_id")
+            .and_then(Value::as_str)
+            .and_then(|s| Uuid::parse_str(s).ok())
+        else {
+            return;
+        };
+        let recorded_at = obj
+            .get("recorded_at")
+            .and_then(Value::as_str)
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|d| d.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now);
+
+        for (field, v) in obj {
+            let value = match v {
+                Value::Number(n) => n.as_f64(),
+                Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+                _ => None,
+            };
+            if let Some(value) = value {
+                self.values
+                    .insert((mp_id, field.clone()), LatestValue { value, recorded_at });
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::model::{
+    gems_3005::data_models::RequestBody,
+    quality::quality_models::{QualityFlag, QualityRule, QualityRuleTable},
+};
+
+struct FieldHistory {
+    value: f64,
+    recorded_at: DateTime<Utc>,
+    repeats: u32, // 같은 값이 연속으로 나온 횟수 (처음 = 1)
+}
+
+// 변화율/고착 판정을 위한 (measurement_point_id, field) 별 직전 값
+#[derive(Default)]
+pub struct QualityTracker {
+    history: DashMap<(Uuid, String), FieldHistory>,
+}
+
+impl QualityTracker {
+    // 본문의 레코드마다 규칙이 있는 필드(와 보간한 필드)에 "quality": { field: flag } 를 붙인다
+    pub fn annotate_body(&self, table: &QualityRuleTable, body: &mut RequestBody) {
+        let rules = table.rules(&body.sensor_type);
+        if let Value::Array(records) = &mut body.data {
+            for record in records {
+                self.annotate(&rules, record);
+            }
+        }
+    }
+
+    pub fn annotate(&self, rules: &[&QualityRule], record: &mut Value) {
+        let Some(obj) = record.as_object_mut() else {
+            return;
+        };
+        let Some(mp_id) = obj
+            .get("measurement_point_id")
+            .and_then(Value::as_str)
+            .and_then(|s| Uuid::parse_str(s).ok())
+        else {
+            return;
+        };
+        let recorded_at = obj
+            .get("recorded_at")
+            .and_then(Value::as_str)
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now);
+
+        let mut quality = Map::new();
+        for rule in rules {
+            let value = obj.get(&rule.field).and_then(Value::as_f64);
+            // 레코드에 없는 필드(다른 모델)의 규칙은 건너뛴다
+            if value.is_none() && !obj.contains_key(&rule.field) {
+                continue;
+            }
+            let flag = self.check(mp_id, rule, value, recorded_at);
+            quality.insert(rule.field.clone(), serde_json::to_value(flag).unwrap_or(Value::Null));
+        }
+
+        // 백필 레코드: "interpolated": [field, ...] 를 품질 플래그로 옮긴다
+        if let Some(Value::Array(fields)) = obj.remove("interpolated") {
+            let flag = serde_json::to_value(QualityFlag::Interpolated).unwrap_or(Value::Null);
+            for field in fields.iter().filter_map(Value::as_str) {
+                quality.insert(field.to_owned(), flag.clone());
+            }
+        }
+
+        if !quality.is_empty() {
+            obj.insert("quality".to_owned(), Value::Object(quality));
+        }
+    }
+
+    fn check(
+        &self,
+        mp_id: Uuid,
+        rule: &QualityRule,
+        value: Option<f64>,
+        recorded_at: DateTime<Utc>,
+    ) -> QualityFlag {
+        let Some(value) = value else {
+            return QualityFlag::Missing;
+        };
+
+        let key = (mp_id, rule.field.clone());
+        let previous = self.history.get(&key).map(|h| (h.value, h.recorded_at, h.repeats));
+        let repeats = match previous {
+            Some((prev, _, n)) if prev == value => n + 1,
+            _ => 1,
+        };
+        self.history.insert(
+            key,
+            FieldHistory {
+                value,
+                recorded_at,
+                repeats,
+            },
+        );
+
+        if rule.min.is_some_and(|min| value < min) || rule.max.is_some_and(|max| value > max) {
+            return QualityFlag::OutOfRange;
+        }
+
+        if let (Some(limit), Some((prev, prev_at, _))) = (rule.max_change_per_minute, previous) {
+            let minutes = ((recorded_at - prev_at).num_seconds().max(60)) as f64 / 60.0;
+            if (value - prev).abs() / minutes > limit {
+                return QualityFlag::RateOfChange;
+            }
+        }
+
+        if rule.stuck_cycles.is_some_and(|n| n > 1 && repeats >= n) {
+            return QualityFlag::Stuck;
+        }
+
+        QualityFlag::Good
+    }
+}
@@ -0,0 +1,265 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    model::{
+        gems_3005::data_models::RequestBody,
+        rollup::rollup_models::{RollupWindow, RollupWindowTable},
+    },
+    service::utils::create_time::window_bounds,
+};
+
+// 순시값 필드 통계
+struct FieldStat {
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: u32,
+    last: f64,
+}
+
+// 적산값 필드: 구간 사용량 합계. delta 가 없는 샘플(첫 수집/리셋/급증)은 gaps 로 센다
+#[derive(Default)]
+struct CounterStat {
+    delta: f64,
+    gaps: u32,
+    last: Option<f64>,
+}
+
+struct WindowAcc {
+    building_id: Uuid,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    samples: u32,
+    fields: BTreeMap<String, FieldStat>,
+    counters: BTreeMap<String, CounterStat>,
+}
+
+impl WindowAcc {
+    fn new(building_id: Uuid, (start, end): (DateTime<Utc>, DateTime<Utc>)) -> Self {
+        WindowAcc {
+            building_id,
+            start,
+            end,
+            samples: 0,
+            fields: BTreeMap::new(),
+            counters: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, obj: &Map<String, Value>) {
+        self.samples += 1;
+
+        // xxx_delta 가 있는 xxx 는 적산값
+        let counters: BTreeSet<&str> = obj
+            .keys()
+            .filter_map(|k| k.strip_suffix("_delta"))
+            .filter(|k| obj.contains_key(*k))
+            .collect();
+
+        for (key, value) in obj {
+            if key.ends_with("_delta") {
+                continue;
+            }
+            if counters.contains(key.as_str()) {
+                let stat = self.counters.entry(key.clone()).or_default();
+                match obj.get(&format!("{}_delta", key)).and_then(Value::as_f64) {
+                    Some(delta) => stat.delta += delta,
+                    None => stat.gaps += 1,
+                }
+                if let Some(v) = value.as_f64() {
+                    stat.last = Some(v);
+                }
+                continue;
+            }
+
+            let Some(v) = value.as_f64() else {
+                continue;
+            };
+            if key == "building_id" || key == "measurement_point_id" {
+                continue;
+            }
+            self.fields
+                .entry(key.clone())
+                .and_modify(|s| {
+                    s.min = s.min.min(v);
+                    s.max = s.max.max(v);
+                    s.sum += v;
+                    s.count += 1;
+                    s.last = v;
+                })
+                .or_insert(FieldStat {
+                    min: v,
+                    max: v,
+                    sum: v,
+                    count: 1,
+                    last: v,
+                });
+        }
+    }
+
+    fn to_record(&self, measurement_point_id: Uuid) -> Value {
+        let fields: Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|(k, s)| {
+                let stat = json!({
+                    "min": s.min,
+                    "max": s.max,
+                    "mean": s.sum / s.count as f64,
+                    "last": s.last,
+                    "count": s.count,
+                });
+                (k.clone(), stat)
+            })
+            .collect();
+        let counters: Map<String, Value> = self
+            .counters
+            .iter()
+            .map(|(k, s)| {
+                let stat = json!({
+                    "delta": (s.delta * 1e6).round() / 1e6,
+                    "gaps": s.gaps,
+                    "last": s.last,
+                });
+                (k.clone(), stat)
+            })
+            .collect();
+
+        json!({
+            "building_id": self.building_id,
+            "measurement_point_id": measurement_point_id,
+            "window_start": self.start,
+            "window_end": self.end,
+            "samples": self.samples,
+            "fields": fields,
+            "counters": counters,
+        })
+    }
+}
+
+// (구간 설정 이름, measurement_point_id) 별로 진행 중인 구간을 들고 있다가
+// 구간이 끝나면 롤업 레코드로 내보낸다. 메모리에만 있어 재시작 시 진행 중이던 구간은 버려진다
+#[derive(Default)]
+pub struct RollupEngine {
+    open: DashMap<(String, Uuid), WindowAcc>,
+}
+
+impl RollupEngine {
+    // 한 주기 본문을 누적하고, 끝난 구간의 롤업 본문(구간 설정 이름이 sensor_type)을 돌려준다
+    pub fn observe(
+        &self,
+        table: &RollupWindowTable,
+        sensor_type: &str,
+        bodies: &[RequestBody],
+    ) -> Vec<RequestBody> {
+        let windows = table.for_sensor(sensor_type);
+        if windows.is_empty() {
+            return Vec::new();
+        }
+
+        let records: Vec<&Map<String, Value>> = bodies
+            .iter()
+            .filter_map(|b| b.data.as_array())
+            .flatten()
+            .filter_map(Value::as_object)
+            .collect();
+        let cycle_at = records
+            .iter()
+            .filter_map(|o| recorded_at(o))
+            .max()
+            .unwrap_or_else(Utc::now);
+
+        let mut bodies = Vec::new();
+        for window in windows {
+            let mut closed: Vec<(Uuid, WindowAcc)> = Vec::new();
+
+            // 이번 주기 시각으로 끝난 구간 (수집이 끊긴 포인트 포함)
+            let done: Vec<(String, Uuid)> = self
+                .open
+                .iter()
+                .filter(|e| e.key().0 == window.name && e.end <= cycle_at)
+                .map(|e| e.key().clone())
+                .collect();
+            for key in done {
+                if let Some(((_, mp), acc)) = self.open.remove(&key) {
+                    closed.push((mp, acc));
+                }
+            }
+
+            for obj in &records {
+                self.add_record(window, obj, &mut closed);
+            }
+
+            if !closed.is_empty() {
+                info!("Closed {} {} rollup window(s)", closed.len(), window.name);
+            }
+            bodies.extend(to_bodies(&window.name, closed));
+        }
+        bodies
+    }
+
+    fn add_record(
+        &self,
+        window: &RollupWindow,
+        obj: &Map<String, Value>,
+        closed: &mut Vec<(Uuid, WindowAcc)>,
+    ) {
+        let (Some(mp), Some(building_id), Some(at)) = (
+            uuid_field(obj, "measurement_point_id"),
+            uuid_field(obj, "building_id"),
+            recorded_at(obj),
+        ) else {
+            return;
+        };
+        let bounds = window_bounds(at, window.window_minutes, &window.align);
+
+        let key = (window.name.clone(), mp);
+        let mut acc = self
+            .open
+            .entry(key)
+            .or_insert_with(|| WindowAcc::new(building_id, bounds));
+        // 다른 구간의 샘플이 오면 (늦게 온 레코드 등) 진행 중이던 구간을 닫는다
+        if acc.start != bounds.0 {
+            let finished = std::mem::replace(&mut *acc, WindowAcc::new(building_id, bounds));
+            closed.push((mp, finished));
+        }
+        acc.add(obj);
+    }
+}
+
+fn to_bodies(name: &str, closed: Vec<(Uuid, WindowAcc)>) -> Vec<RequestBody> {
+    let mut buildings: BTreeMap<Uuid, Vec<Value>> = BTreeMap::new();
+    for (mp, acc) in closed {
+        buildings
+            .entry(acc.building_id)
+            .or_default()
+            .push(acc.to_record(mp));
+    }
+
+    buildings
+        .into_iter()
+        .map(|(building_id, data)| RequestBody {
+            sensor_type: name.to_owned(),
+            building_id,
+            data: Value::Array(data),
+        })
+        .collect()
+}
+
+fn uuid_field(obj: &Map<String, Value>, name: &str) -> Option<Uuid> {
+    obj.get(name)
+        .and_then(Value::as_str)
+        .and_then(|s| Uuid::parse_str(s).ok())
+}
+
+fn recorded_at(obj: &Map<String, Value>) -> Option<DateTime<Utc>> {
+    obj.get("recorded_at")
+        .and_then(Value::as_str)
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
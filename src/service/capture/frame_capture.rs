@@ -0,0 +1,299 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashSet;
+use serde_derive::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, SyncSender},
+    },
+    thread,
+};
+use tokio_modbus::ExceptionCode;
+use tracing::error;
+
+use crate::service::{
+    read::iaq::{frame_parser::HEADER_LEN, util_funcs::format_mac_upper},
+    utils::frame_log::{FrameLogEntry, to_hex},
+};
+
+const CAPTURE_DIR: &str = "captures";
+const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+const MAX_FILES_PER_STREAM: usize = 20;
+// 쓰기 스레드가 밀리면 이 이상은 버린다 (수집/수신 경로를 막지 않는다)
+const WRITE_QUEUE: usize = 4096;
+
+// MAC 위치: 헤더 + fc(1) + local_addr(6) + ssid(32)
+const MAC_OFFSET: usize = HEADER_LEN + 1 + 6 + 32;
+
+// 파일 하나 (크기 초과시 새 파일로 교체, 오래된 파일 삭제)
+struct RotatingFile {
+    stream: &'static str,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn new(stream: &'static str) -> Self {
+        RotatingFile {
+            stream,
+            file: None,
+            written: 0,
+        }
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.file.is_none() || self.written >= MAX_FILE_BYTES {
+            self.rotate()?;
+        }
+        if let Some(file) = self.file.as_mut() {
+            writeln!(file, "{}", line)?;
+            self.written += line.len() as u64 + 1;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        fs::create_dir_all(CAPTURE_DIR)?;
+        let name = format!(
+            "{}-{}.log",
+            self.stream,
+            Utc::now().format("%Y%m%d-%H%M%S%.3f")
+        );
+        let path: PathBuf = [CAPTURE_DIR, &name].iter().collect();
+        self.file = Some(OpenOptions::new().create(true).append(true).open(path)?);
+        self.written = 0;
+
+        // 스트림별 최대 파일 수 유지 (이름에 시각이 들어 있어 정렬 = 생성순)
+        let prefix = format!("{}-", self.stream);
+        let mut files: Vec<PathBuf> = fs::read_dir(CAPTURE_DIR)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".log"))
+            })
+            .collect();
+        files.sort();
+        while files.len() > MAX_FILES_PER_STREAM {
+            let _ = fs::remove_file(files.remove(0)); // 실패 무시
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PduDirection {
+    Request,
+    Response,
+}
+
+// Modbus PDU 기록 (한 줄에 한 PDU). UDP 프레임 로그와 열 수가 달라 udp-replay 가 읽지 않는다:
+//   <RFC3339 시각>\t<장치 키>\t<request|response>\t<16진수 PDU>
+#[derive(Debug, Clone)]
+pub struct ModbusPduEntry {
+    pub at: DateTime<Utc>,
+    pub device: String,
+    pub direction: PduDirection,
+    pub pdu: Vec<u8>,
+}
+
+impl ModbusPduEntry {
+    pub fn to_line(&self) -> String {
+        let direction = match self.direction {
+            PduDirection::Request => "request",
+            PduDirection::Response => "response",
+        };
+        format!(
+            "{}\t{}\t{}\t{}",
+            self.at.to_rfc3339(),
+            self.device,
+            direction,
+            to_hex(&self.pdu)
+        )
+    }
+}
+
+// 쓰기 스레드로 보내는 한 줄
+enum CaptureLine {
+    Udp(String),
+    Modbus(String),
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CaptureStatus {
+    pub directory: &'static str,
+    pub devices: Vec<String>,
+    pub dropped: u64, // 쓰기 대기열이 차서 버린 줄 수
+}
+
+// 장치별로 켜고 끄는 원시 프레임 캡처.
+// UDP 장치 키는 MAC("AA:BB:..") 또는 송신 IP, Modbus 장치 키는 "ip:port/unit".
+// 파일 쓰기는 처음 캡처할 때 띄우는 전용 스레드가 맡는다 (런타임 워커에서 동기 IO 를 하지 않는다)
+#[derive(Default)]
+pub struct FrameCapture {
+    devices: DashSet<String>,
+    writer: OnceLock<Option<SyncSender<CaptureLine>>>,
+    dropped: AtomicU64,
+}
+
+impl FrameCapture {
+    pub fn enable(&self, device: &str) -> bool {
+        self.devices.insert(device.to_uppercase())
+    }
+
+    pub fn disable(&self, device: &str) -> bool {
+        self.devices.remove(&device.to_uppercase()).is_some()
+    }
+
+    pub fn is_enabled(&self, device: &str) -> bool {
+        !self.devices.is_empty() && self.devices.contains(&device.to_uppercase())
+    }
+
+    pub fn status(&self) -> CaptureStatus {
+        let mut devices: Vec<String> = self.devices.iter().map(|d| d.clone()).collect();
+        devices.sort();
+        CaptureStatus {
+            directory: CAPTURE_DIR,
+            devices,
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    // 수신한 UDP 데이터그램 그대로 (파싱 전)
+    pub fn capture_udp(&self, peer: SocketAddr, data: &[u8]) {
+        if self.devices.is_empty() {
+            return;
+        }
+        let mac = data
+            .get(MAC_OFFSET..MAC_OFFSET + 6)
+            .map(format_mac_upper)
+            .unwrap_or_default();
+        if !self.is_enabled(&mac) && !self.is_enabled(&peer.ip().to_string()) {
+            return;
+        }
+
+        let entry = FrameLogEntry {
+            at: Utc::now(),
+            source: peer.to_string(),
+            bytes: data.to_vec(),
+        };
+        self.send(CaptureLine::Udp(entry.to_line()));
+    }
+
+    // Modbus 읽기 요청/응답 PDU
+    pub fn capture_modbus(
+        &self,
+        device: &str,
+        function_code: u8,
+        address: u16,
+        count: u16,
+        response: &Result<Vec<u16>, ExceptionCode>,
+    ) {
+        if !self.is_enabled(device) {
+            return;
+        }
+
+        let mut request = vec![function_code];
+        request.extend_from_slice(&address.to_be_bytes());
+        request.extend_from_slice(&count.to_be_bytes());
+
+        let response = match response {
+            Ok(words) => {
+                let mut pdu = vec![function_code, (words.len() * 2) as u8];
+                for w in words {
+                    pdu.extend_from_slice(&w.to_be_bytes());
+                }
+                pdu
+            }
+            Err(code) => vec![function_code | 0x80, u8::from(*code)],
+        };
+
+        let now = Utc::now();
+        for (direction, pdu) in [
+            (PduDirection::Request, request),
+            (PduDirection::Response, response),
+        ] {
+            let entry = ModbusPduEntry {
+                at: now,
+                device: device.to_owned(),
+                direction,
+                pdu,
+            };
+            self.send(CaptureLine::Modbus(entry.to_line()));
+        }
+    }
+
+    fn send(&self, line: CaptureLine) {
+        let Some(tx) = self.writer.get_or_init(spawn_writer) else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        };
+        // 가득 찼거나 스레드가 끝났으면 버린다
+        if tx.try_send(line).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+// FrameCapture 가 drop 되면 송신측이 닫혀 스레드도 끝난다
+fn spawn_writer() -> Option<SyncSender<CaptureLine>> {
+    let (tx, rx) = mpsc::sync_channel::<CaptureLine>(WRITE_QUEUE);
+    let spawned = thread::Builder::new()
+        .name("frame-capture".to_owned())
+        .spawn(move || {
+            let mut udp = RotatingFile::new("udp");
+            let mut modbus = RotatingFile::new("modbus");
+            for line in rx {
+                let (file, line) = match line {
+                    CaptureLine::Udp(line) => (&mut udp, line),
+                    CaptureLine::Modbus(line) => (&mut modbus, line),
+                };
+                if let Err(e) = file.write_line(&line) {
+                    error!("Could not write {} capture: {:?}", file.stream, e);
+                }
+            }
+        });
+    match spawned {
+        Ok(_) => Some(tx),
+        Err(e) => {
+            error!("Could not start frame capture writer: {:?}", e);
+            None
+        }
+    }
+}
+
+// Modbus 캡처용 장치 키
+pub fn modbus_device_key(host: impl std::fmt::Display, port: u16, unit_id: u8) -> String {
+    format!("{}:{}/{}", host, port, unit_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modbus_pdus_are_not_frame_log_lines() {
+        let entry = ModbusPduEntry {
+            at: Utc::now(),
+            device: modbus_device_key("10.0.0.1", 502, 1),
+            direction: PduDirection::Response,
+            pdu: vec![0x04, 0x02, 0x00, 0x2a],
+        };
+        let line = entry.to_line();
+        assert!(line.ends_with("\t10.0.0.1:502/1\tresponse\t0402002a"));
+        assert!(FrameLogEntry::from_line(&line).is_err());
+    }
+
+    #[test]
+    fn capture_skips_disabled_devices_without_a_writer() {
+        let capture = FrameCapture::default();
+        capture.capture_modbus("10.0.0.1:502/1", 0x04, 0, 1, &Ok(vec![1]));
+        assert!(capture.writer.get().is_none());
+        assert_eq!(capture.status().dropped, 0);
+    }
+}
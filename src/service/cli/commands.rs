@@ -0,0 +1,175 @@
+use anyhow::{Result, anyhow};
+use serde_json::{Value, to_string_pretty, to_value};
+use uuid::Uuid;
+
+use crate::{
+    model::{
+        iaq::data_models::IaqRegisterMapTable,
+        modbus::{
+            gateway_map_models::GatewayRegisterMapTable,
+            memory_map_models::MemoryMapRegistry,
+            write_models::WriteAllowlist,
+        },
+        sihas::data_models::SihasRegisterMapTable,
+        virtual_point::virtual_point_models::VirtualPointTable,
+    },
+    service::{
+        collect::{
+            collection_plan::CollectMode,
+            gas::{collect_gas_bodies, read_gas_point},
+            gems_3500_modbus::{collect_gems_3500_bodies, read_gems_points},
+            heat::{collect_heat_bodies, read_heat_point},
+        },
+        config::validation::validate_points,
+        utils::create_time::utc_now_minute,
+        server::get_state::get_state,
+    },
+};
+
+pub const USAGE: &str = "usage: modbus_data_collection [serve | validate | collect-once --sensor gems|heat|gas | read --point <measurement_point_id> | scan ... | simulate ... | udp-send ... | udp-replay ...]";
+
+// 인자에서 --flag 값 하나 꺼내기
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<&'a str> {
+    match args {
+        [f, value] if f == flag => Ok(value.as_str()),
+        [f] if f == flag => Err(anyhow!("Missing value for {}", flag)),
+        [] => Err(anyhow!("{} is required\n{}", flag, USAGE)),
+        [other, ..] => Err(anyhow!("Unknown option: {}\n{}", other, USAGE)),
+    }
+}
+
+fn print_json(value: &Value) -> Result<()> {
+    println!("{}", to_string_pretty(value)?);
+    Ok(())
+}
+
+// validate: 모든 CSV / 메모리 맵을 읽고 문제를 파일:행:열 단위로 모아 보고한다
+pub async fn run_validate() -> Result<()> {
+    let mut failed = 0;
+    let mut check = |name: &str, res: Result<usize>| match res {
+        Ok(rows) => println!("ok    {} ({} rows)", name, rows),
+        Err(e) => {
+            println!("error {}: {}", name, e);
+            failed += 1;
+        }
+    };
+
+    let memory_maps = MemoryMapRegistry::from_csv().map_err(|e| anyhow!("{}", e));
+    check(
+        "memory_maps.csv",
+        memory_maps.as_ref().map(|r| r.maps.len()).map_err(|e| anyhow!("{}", e)),
+    );
+    if let Ok(registry) = &memory_maps {
+        let mut models: Vec<_> = registry.maps.values().collect();
+        models.sort_by(|a, b| a.model.cmp(&b.model));
+        for table in models {
+            check(&format!("{} memory map", table.model), Ok(table.rows.len()));
+        }
+    }
+    let iaq_table = IaqRegisterMapTable::from_csv();
+    check(
+        "iaq_register_map.csv",
+        iaq_table.as_ref().map(|t| t.rows.len()).map_err(|e| anyhow!("{}", e)),
+    );
+    check(
+        "sihas_register_map.csv",
+        SihasRegisterMapTable::from_csv().map(|t| t.rows.len()),
+    );
+    check(
+        "modbus_gateway_map.csv",
+        GatewayRegisterMapTable::from_csv().map(|t| t.rows.len()),
+    );
+    check(
+        "modbus_write_allowlist.csv",
+        WriteAllowlist::from_csv().map(|t| t.rows.len()),
+    );
+
+    // 측정 포인트는 메모리 맵 / IAQ 레지스터 맵이 있어야 교차 검증할 수 있다
+    let (Ok(memory_maps), Ok(iaq_table)) = (memory_maps, iaq_table) else {
+        return Err(anyhow!("{} configuration problem(s) found", failed));
+    };
+    let points = validate_points(&memory_maps, &iaq_table)?;
+    for (name, rows) in [
+        ("gems.csv", points.gems.len()),
+        ("heat.csv", points.heat.len()),
+        ("gas.csv", points.gas.len()),
+        ("iaq.csv", points.iaq.len()),
+        ("ccm.csv", points.ccm.len()),
+        ("sihas.csv", points.sihas.len()),
+    ] {
+        println!("      {} ({} valid rows)", name, rows);
+    }
+
+    // 가상 포인트 식은 실제 포인트 id 와 교차 검증
+    match VirtualPointTable::from_csv()
+        .and_then(|t| t.check_references(&points.measurement_point_ids()).map(|_| t.points.len()))
+    {
+        Ok(n) => println!("ok    virtual_points.csv ({} points)", n),
+        Err(e) => {
+            println!("error {}", e);
+            failed += 1;
+        }
+    }
+    for problem in &points.problems {
+        println!("error {}", problem);
+    }
+
+    let total = failed + points.problems.len();
+    if total == 0 {
+        println!("configuration ok");
+        Ok(())
+    } else {
+        Err(anyhow!("{} configuration problem(s) found", total))
+    }
+}
+
+// collect-once --sensor gems|heat|gas : 한 주기 수집 결과를 전송하지 않고 stdout 으로
+// 적산 기준값/알람/집계/품질 이력은 건드리지 않는다 (DryRun)
+pub async fn run_collect_once(args: &[String]) -> Result<()> {
+    let sensor = flag_value(args, "--sensor")?;
+    let state = get_state().await?;
+
+    let bodies = match sensor {
+        "gems" => collect_gems_3500_bodies(&state, CollectMode::DryRun).await?,
+        "heat" => collect_heat_bodies(&state, CollectMode::DryRun).await?,
+        "gas" => collect_gas_bodies(&state, CollectMode::DryRun).await?,
+        other => return Err(anyhow!("Unknown sensor {} (gems|heat|gas)", other)),
+    };
+
+    print_json(&to_value(&bodies)?)
+}
+
+// read --point <measurement_point_id> : 설정된 포인트 하나만 읽어 출력
+pub async fn run_read_point(args: &[String]) -> Result<()> {
+    let id: Uuid = flag_value(args, "--point")?.parse()?;
+    let state = get_state().await?;
+    let slot = utc_now_minute();
+
+    if let Some(plan) = state.collection_plans.gems_for_point(id) {
+        let data = read_gems_points(&state, std::slice::from_ref(&plan), slot).await?;
+        let Some(data) = data.into_iter().next() else {
+            return Err(anyhow!("Could not read GEMS point {} from {}:{}", id, plan.ip, plan.port));
+        };
+        return print_json(&to_value(&data)?);
+    }
+
+    if let Some(mp) = state
+        .heat_measurement_point
+        .iter()
+        .find(|mp| mp.measurement_point_id == id)
+    {
+        let data = read_heat_point(mp, &state.collection_plans.heat_registers, &state.frame_capture, slot).await?;
+        return print_json(&to_value(&data)?);
+    }
+
+    if let Some(mp) = state
+        .gas_measurement_point
+        .iter()
+        .find(|mp| mp.measurement_point_id == id)
+    {
+        let data = read_gas_point(mp, &state.collection_plans.gas_registers, &state.frame_capture, slot).await?;
+        return print_json(&to_value(&data)?);
+    }
+
+    Err(anyhow!("No GEMS/heat/gas measurement point {}", id))
+}
@@ -0,0 +1,202 @@
+use anyhow::{Result, anyhow};
+use std::{collections::BTreeMap, net::IpAddr};
+use uuid::Uuid;
+
+use crate::{
+    model::{
+        gems_3005::data_models::{GemsCollectionSet, GemsMeasurementPoint},
+        modbus::{
+            memory_map_models::{
+                GAS_METER, GEMS_3500, HEAT_METER, MemoryMapRegistry, MemoryMapTable,
+            },
+            modbus_register_models::{ModbusRegister, ModbusRegisterType},
+        },
+    },
+    service::{collect::gems_3500_modbus::register_from_ch, config::validation::ValidatedPoints},
+};
+
+// register_from_ch 결과 중 수출 적산(kwh_export_sum) 위치
+const GEMS_EXPORT_SUM_INDEX: usize = 17;
+
+// 열량계 / 가스 유량계 레코드 필드. 계획의 레지스터는 이 순서로 읽힌다
+pub const HEAT_FIELDS: [&str; 6] = [
+    "instant_flow",
+    "instant_heat",
+    "supply_temperature",
+    "return_temperature",
+    "cumulative_flow",
+    "cumulative_heat",
+];
+pub const GAS_FIELDS: [&str; 4] = ["cumulative_flow", "instant_flow", "pressure", "temp"];
+
+// 한 주기 수집 방식
+//  Live   : 수집 태스크. 적산 기준값 저장, 수요/최신값/품질 이력/수집 시각 갱신, 알람, 집계
+//  DryRun : collect-once. 장치를 읽어 본문까지 만들되 위 상태는 하나도 건드리지 않는다
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectMode {
+    Live,
+    DryRun,
+}
+
+// GEMS 장치(ip, port, unit) 하나에서 읽을 채널 목록. 연결 하나로 순서대로 읽는다
+pub struct GemsEndpointPlan {
+    pub ip: IpAddr,
+    pub port: u16,
+    pub unit_id: u8,
+    pub sets: Vec<GemsCollectionSet>,
+}
+
+// 기동 시 메모리 맵에서 풀어 둔 수집 계획. 주기마다 주소/형식을 다시 찾지 않는다
+#[derive(Default)]
+pub struct CollectionPlans {
+    pub gems: Vec<GemsEndpointPlan>,
+    pub heat_registers: Vec<ModbusRegister>, // HEAT_FIELDS 순서
+    pub gas_registers: Vec<ModbusRegister>,  // GAS_FIELDS 순서
+}
+
+impl CollectionPlans {
+    pub fn compile(
+        memory_maps: &MemoryMapRegistry,
+        points: &ValidatedPoints,
+    ) -> Result<CollectionPlans> {
+        let gems_table = memory_maps.get(GEMS_3500)?;
+
+        let mut endpoints: BTreeMap<(IpAddr, u16, u8), Vec<GemsCollectionSet>> = BTreeMap::new();
+        for point in &points.gems {
+            let set = gems_collection_set(gems_table, point)?;
+            endpoints
+                .entry((point.host, point.port as u16, point.unit_id))
+                .or_default()
+                .push(set);
+        }
+        let gems = endpoints
+            .into_iter()
+            .map(|((ip, port, unit_id), sets)| GemsEndpointPlan {
+                ip,
+                port,
+                unit_id,
+                sets,
+            })
+            .collect();
+
+        // 포인트가 없는 장치 모델은 맵이 없어도 된다
+        let heat_registers = if points.heat.is_empty() {
+            Vec::new()
+        } else {
+            field_registers(memory_maps.get(HEAT_METER)?, &HEAT_FIELDS)?
+        };
+        let gas_registers = if points.gas.is_empty() {
+            Vec::new()
+        } else {
+            field_registers(memory_maps.get(GAS_METER)?, &GAS_FIELDS)?
+        };
+
+        Ok(CollectionPlans {
+            gems,
+            heat_registers,
+            gas_registers,
+        })
+    }
+
+    // 포인트 하나만 읽는 계획 (read --point)
+    pub fn gems_for_point(&self, measurement_point_id: Uuid) -> Option<GemsEndpointPlan> {
+        self.gems.iter().find_map(|plan| {
+            let set = plan
+                .sets
+                .iter()
+                .find(|s| s.measurement_point_id == measurement_point_id)?;
+            Some(GemsEndpointPlan {
+                ip: plan.ip,
+                port: plan.port,
+                unit_id: plan.unit_id,
+                sets: vec![set.clone()],
+            })
+        })
+    }
+}
+
+// 채널 레지스터를 메모리 맵에서 풀어 둔다. 수출 적산을 쓰지 않는 포인트는 읽지 않도록 None 형식
+pub fn gems_collection_set(
+    gems_table: &MemoryMapTable,
+    point: &GemsMeasurementPoint,
+) -> Result<GemsCollectionSet> {
+    let mut registers = Vec::new();
+    for (i, u) in register_from_ch(point.channel).into_iter().enumerate() {
+        let gems_map = gems_table
+            .get(u)
+            .map_err(|e| anyhow!("Could not fetch gems_table: {}", e))?;
+        let mut register = ModbusRegister::from(gems_map);
+        if i == GEMS_EXPORT_SUM_INDEX && !point.export_sum_status {
+            register.value_type = ModbusRegisterType::None;
+        }
+        registers.push(register);
+    }
+
+    Ok(GemsCollectionSet::new(point.clone(), registers))
+}
+
+fn field_registers(table: &MemoryMapTable, fields: &[&str]) -> Result<Vec<ModbusRegister>> {
+    fields.iter().map(|field| table.register(field)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::gems_3005::data_models::GemsSetValue;
+    use chrono::Utc;
+
+    fn gems_point(building: u128, point: u128, host: &str, channel: u16) -> GemsMeasurementPoint {
+        GemsMeasurementPoint {
+            building_id: Uuid::from_u128(building),
+            measurement_point_id: Uuid::from_u128(point),
+            host: host.parse().unwrap(),
+            port: 502,
+            unit_id: 1,
+            channel,
+            export_sum_status: false,
+        }
+    }
+
+    // 한 GEMS 장치를 여러 건물이 나눠 쓰는 경우: 연결은 하나로 묶여도 레코드는 포인트의 건물로 간다
+    #[test]
+    fn shared_gems_endpoint_keeps_point_buildings() {
+        let points = ValidatedPoints {
+            gems: vec![
+                gems_point(0xA, 1, "10.0.0.1", 1),
+                gems_point(0xB, 2, "10.0.0.1", 2),
+                gems_point(0xA, 3, "10.0.0.1", 3),
+                gems_point(0xC, 4, "10.0.0.2", 1),
+            ],
+            heat: Vec::new(),
+            gas: Vec::new(),
+            iaq: Vec::new(),
+            ccm: Vec::new(),
+            sihas: Vec::new(),
+            problems: Vec::new(),
+        };
+        let owner = |mp: Uuid| {
+            points
+                .gems
+                .iter()
+                .find(|p| p.measurement_point_id == mp)
+                .unwrap()
+                .building_id
+        };
+
+        let plans =
+            CollectionPlans::compile(&MemoryMapRegistry::from_csv().unwrap(), &points).unwrap();
+        assert_eq!(plans.gems.len(), 2);
+        assert_eq!(plans.gems[0].sets.len(), 3);
+
+        let now = Utc::now();
+        for set in plans.gems.iter().flat_map(|plan| &plan.sets) {
+            assert_eq!(set.building_id, owner(set.measurement_point_id));
+            let record = set.to_set_data(GemsSetValue::new(), now, now);
+            assert_eq!(record.building_id, set.building_id);
+        }
+
+        let single = plans.gems_for_point(Uuid::from_u128(2)).unwrap();
+        assert_eq!(single.sets.len(), 1);
+        assert_eq!(single.sets[0].building_id, Uuid::from_u128(0xB));
+    }
+}
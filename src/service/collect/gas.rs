@@ -1,21 +1,29 @@
 use anyhow::{Context, Result, anyhow};
-use byteorder::{BigEndian, ByteOrder};
 use chrono::{DateTime, Utc};
-use serde_json::to_value;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio_modbus::{
-    client::{Context as ModbusContext, Reader},
-    prelude::*,
-};
+use tokio_modbus::prelude::*;
 use tracing::{error, info};
 
 use crate::{
     model::{
-        gas::data_models::GasData,
+        gas::data_models::{GasData, GasMeasurementPoint},
         gems_3005::data_models::{GAS, RequestBody},
+        modbus::modbus_register_models::ModbusRegister,
+    },
+    service::{
+        alarm::alarm_engine::evaluate_alarms,
+        cache::{
+            backfill::{interpolate_counter, missing_slots},
+            counter_deltas::{CounterStore, GAS_CUMULATIVE},
+        },
+        capture::frame_capture::{FrameCapture, modbus_device_key},
+        collect::collection_plan::CollectMode,
+        read::read_from_register::read_registers,
+        server::get_state::ServerState,
+        task::common_scheduling::SECONDS_5MINUTE,
+        utils::create_time::slot_start,
     },
-    service::{server::get_state::ServerState, utils::create_time::utc_now_minute},
 };
 
 use super::gems_3500_modbus::post_axum_server_direct_data;
@@ -23,103 +31,233 @@ use super::gems_3500_modbus::post_axum_server_direct_data;
 pub async fn handle_gas_data(state: Arc<ServerState>) -> Result<()> {
     let start = Instant::now();
 
+    // HTTP POST (건물마다 한 번)
+    for params in collect_gas_bodies(&state, CollectMode::Live).await? {
+        let building_id = params.building_id;
+        if let Err(e) = post_axum_server_direct_data(params).await {
+            error!(
+                "Error posting GAS data for building {} to Axum server: {:?}",
+                building_id, e
+            );
+        } else {
+            info!(
+                "Successfully posted GAS data for building {}: {:?}",
+                building_id,
+                start.elapsed()
+            );
+        }
+    }
+    Ok(())
+}
+
+// 한 주기 수집 후 건물별 RequestBody 까지 만든다 (전송은 호출한 쪽에서)
+// mode 가 DryRun 이면 상태를 바꾸지 않는다 (CollectMode 참고)
+pub async fn collect_gas_bodies(
+    state: &ServerState,
+    mode: CollectMode,
+) -> Result<Vec<RequestBody>> {
     let measurement_points = state.gas_measurement_point.clone();
 
     if measurement_points.is_empty() {
         info!("No gas measurement points—skipping data collection");
-        return Ok(());
+        return Ok(Vec::new());
     }
 
-    let building_id = measurement_points[0].building_id;
-
+    let registers = &state.collection_plans.gas_registers;
+    let slot = slot_start(Utc::now(), SECONDS_5MINUTE as i64);
     let mut records: Vec<GasData> = Vec::new();
 
     for row in &measurement_points {
-        let socket_addr = format!("{}:{}", row.host, row.port).parse()?;
-
-        let mut client = tcp::connect_slave(socket_addr, Slave::from(row.unit_id)).await?;
-
-        // 적산유량 (m³)
-        let reg = client
-            .read_input_registers(0x00, 2)
-            .await?
-            .context("Failed to read cumulative flow registers")?;
-        let cumulative_flow = (reg[0] as u32) * 10_000 + (reg[1] as u32);
-
-        // 순시유량 (m³/h)
-        let reg = client
-            .read_input_registers(0x02, 2)
-            .await?
-            .context("Failed to read instant flow registers")?;
-        let instant_flow = (reg[0] as f64) * 100.0 + (reg[1] as f64) / 100.0;
-
-        // 압력 (kPa)
-        let reg = client
-            .read_input_registers(0x08, 2)
-            .await?
-            .context("Failed to read pressure registers")?;
-
-        let pressure = (reg[0] as f64) * 100.0 + (reg[1] as f64) / 100.0;
-
-        // 온도 (°C)
-        let reg = client
-            .read_input_registers(0x0A, 2)
-            .await?
-            .context("Failed to read temperature registers")?;
-
-        let temp = if reg[0] == 0 {
-            reg[1] as f64 / 100.0
-        } else {
-            -(reg[1] as f64) / 100.0
-        };
+        // 한 대가 실패해도 나머지는 수집 (빠진 포인트는 오프라인 알람으로)
+        match read_gas_point(row, registers, &state.frame_capture, slot).await {
+            Ok(record) => records.push(record),
+            Err(e) => error!(
+                "Failed to read gas point {}: {:?}",
+                row.measurement_point_id, e
+            ),
+        }
+    }
+    if mode == CollectMode::Live {
+        state.acquisition_timing.check(
+            GAS,
+            slot,
+            SECONDS_5MINUTE as i64,
+            records
+                .iter()
+                .map(|r| (r.measurement_point_id, r.acquired_at)),
+        );
+    }
+    backfill_gaps(state, &mut records);
+    apply_counter_deltas(state, &mut records, mode);
+    if mode == CollectMode::Live
+        && let Err(e) = state.counter_store.save().await
+    {
+        error!("Could not save counter state: {:?}", e);
+    }
 
-        // 연결 종료는 drop으로 자동 처리됩니다
-        drop(client);
+    if mode == CollectMode::DryRun {
+        return RequestBody::per_building(GAS, records, |r| r.building_id);
+    }
 
-        let now: DateTime<Utc> = utc_now_minute();
+    state.latest_values.update_from_records(&records);
 
-        records.push(GasData {
-            building_id,
-            measurement_point_id: row.measurement_point_id,
-            recorded_at: now,
-            instant_flow: Some(instant_flow as f64),
-            cumulative_flow: Some(cumulative_flow as f64),
-            pressure: Some(pressure as f64),
-            temp: Some(temp as f64),
-        })
+    let mut bodies = RequestBody::per_building(GAS, records, |r| r.building_id)?;
+    for body in &mut bodies {
+        state
+            .quality_tracker
+            .annotate_body(&state.quality_rule_table, body);
     }
+    evaluate_alarms(state, GAS, &bodies);
 
-    // HTTP POST
-    let params = RequestBody {
-        sensor_type: GAS.to_owned(),
-        building_id,
-        data: to_value(&records).context("Failed to convert records to JSON Value")?,
-    };
+    // 끝난 집계 구간은 별도 sensor_type 본문으로 같이 보낸다
+    let rollups = state
+        .rollup_engine
+        .observe(&state.rollup_window_table, GAS, &bodies);
+    bodies.extend(rollups);
+    Ok(bodies)
+}
+
+// 수집이 빠졌던 슬롯을 적산유량 보간 레코드로 채운다 (유량계 이력 레지스터는 매핑돼 있지 않음)
+fn backfill_gaps(state: &ServerState, records: &mut Vec<GasData>) {
+    let mut filled = Vec::new();
+    for r in records.iter() {
+        let mp = r.measurement_point_id;
+        let Some(prev) = state.counter_store.last(mp, "cumulative_flow") else {
+            continue;
+        };
+
+        for slot in missing_slots(
+            mp,
+            prev.last_read_at(),
+            r.recorded_at,
+            SECONDS_5MINUTE as i64,
+        ) {
+            let Some(cumulative_flow) =
+                interpolate_counter(Some(prev), r.cumulative_flow, r.recorded_at, slot)
+            else {
+                break;
+            };
 
-    if let Err(e) = post_axum_server_direct_data(params).await {
-        error!("Error posting GAS data to Axum server: {:?}", e);
-    } else {
-        info!("Successfully posted GAS data: {:?}", start.elapsed());
+            filled.push(GasData {
+                building_id: r.building_id,
+                measurement_point_id: mp,
+                recorded_at: slot,
+                acquired_at: Utc::now(),
+                interpolated: vec!["cumulative_flow".to_owned()],
+                instant_flow: None,
+                cumulative_flow: Some(cumulative_flow),
+                pressure: None,
+                temp: None,
+                cumulative_flow_delta: None,
+                cumulative_flow_delta_flag: None,
+            });
+        }
+    }
+
+    if !filled.is_empty() {
+        filled.append(records);
+        *records = filled;
     }
-    Ok(())
 }
 
-// 레지스터에서 수집한 데이터 포맷팅
-fn data_format(reg: &[u16]) -> Result<f32> {
-    if reg.len() != 2 {
-        anyhow::bail!("Expected 2 registers, got {}", reg.len());
+// 적산유량 → 구간 사용량
+fn apply_counter_deltas(state: &ServerState, records: &mut [GasData], mode: CollectMode) {
+    let counters = &state.counter_store;
+    let observe = match mode {
+        CollectMode::Live => CounterStore::observe,
+        CollectMode::DryRun => CounterStore::preview,
+    };
+    for r in records.iter_mut() {
+        (r.cumulative_flow_delta, r.cumulative_flow_delta_flag) = observe(
+            counters,
+            r.measurement_point_id,
+            "cumulative_flow",
+            r.cumulative_flow,
+            r.recorded_at,
+            GAS_CUMULATIVE,
+        );
     }
-    let mut buf = [0u8; 4];
-    BigEndian::write_u16(&mut buf[0..2], reg[1]);
-    BigEndian::write_u16(&mut buf[2..4], reg[0]);
-    Ok(BigEndian::read_f32(&buf))
 }
 
-async fn get_modbus_data(client: &mut ModbusContext, start_addr: u16, length: u16) -> Result<f32> {
-    let regs = client
-        .read_holding_registers(start_addr, length)
-        .await?
-        .map_err(|e| anyhow!("Heat Modbus read error: {:?}", e))?;
+// 가스 유량계 한 대 읽기. registers 는 기동 시 gas 메모리 맵에서 풀어 둔 GAS_FIELDS 순서
+// recorded_at 은 예정 슬롯, acquired_at 은 실제 읽은 시각
+pub async fn read_gas_point(
+    row: &GasMeasurementPoint,
+    registers: &[ModbusRegister],
+    capture: &FrameCapture,
+    slot: DateTime<Utc>,
+) -> Result<GasData> {
+    let socket_addr = format!("{}:{}", row.host, row.port).parse()?;
+
+    let mut client = tcp::connect_slave(socket_addr, Slave::from(row.unit_id)).await?;
+    let device = modbus_device_key(row.host, row.port as u16, row.unit_id);
+    let values = read_registers(&mut client, registers, capture, &device)
+        .await
+        .context("Failed to read gas registers")?;
+
+    // 연결 종료는 drop으로 자동 처리됩니다
+    drop(client);
+
+    gas_record(row, &values, slot, Utc::now())
+}
+
+// 계획 순서(GAS_FIELDS)로 읽은 값 → 포인트의 건물/측정점으로 레코드 생성
+fn gas_record(
+    row: &GasMeasurementPoint,
+    values: &[Option<f64>],
+    slot: DateTime<Utc>,
+    acquired_at: DateTime<Utc>,
+) -> Result<GasData> {
+    // 적산유량 (m³), 순시유량 (m³/h), 압력 (kPa), 온도 (°C)
+    let &[cumulative_flow, instant_flow, pressure, temp] = values else {
+        return Err(anyhow!(
+            "Gas collection plan has {} registers",
+            values.len()
+        ));
+    };
+
+    Ok(GasData {
+        building_id: row.building_id,
+        measurement_point_id: row.measurement_point_id,
+        recorded_at: slot,
+        acquired_at,
+        interpolated: Vec::new(),
+        instant_flow,
+        cumulative_flow,
+        pressure,
+        temp,
+        cumulative_flow_delta: None,
+        cumulative_flow_delta_flag: None,
+    })
+}
 
-    data_format(&regs)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn point(building: u128, point: u128) -> GasMeasurementPoint {
+        GasMeasurementPoint {
+            building_id: Uuid::from_u128(building),
+            measurement_point_id: Uuid::from_u128(point),
+            host: "127.0.0.1".parse().unwrap(),
+            port: 502,
+            unit_id: point as u8,
+        }
+    }
+
+    #[test]
+    fn record_keeps_its_point_ids() {
+        let now = Utc::now();
+        let values = [Some(10.0), Some(1.5), Some(2.0), Some(15.0)];
+        let record = gas_record(&point(0xB, 2), &values, now, now).unwrap();
+        assert_eq!(record.building_id, Uuid::from_u128(0xB));
+        assert_eq!(record.measurement_point_id, Uuid::from_u128(2));
+    }
+
+    #[test]
+    fn wrong_register_count_is_an_error() {
+        let now = Utc::now();
+        assert!(gas_record(&point(0xA, 1), &[Some(1.0)], now, now).is_err());
+    }
 }
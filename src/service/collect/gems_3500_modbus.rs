@@ -1,76 +1,170 @@
 use anyhow::{Result, anyhow};
-use dashmap::DashMap;
+use chrono::{DateTime, Utc};
 use futures::stream::{FuturesUnordered, StreamExt};
 use reqwest::Client;
-use std::net::IpAddr;
 use std::sync::Arc;
-use tracing::{error, warn};
+use tracing::{error, info, warn};
 use tokio::time::{Duration, timeout};
 
 use crate::{
     model::{
-        gems_3005::data_models::{
-            GemsCollectionSet, GemsSetData, RequestBody, GEMS,
-        },
-        modbus::modbus_register_models::ModbusRegister,
+        gems_3005::data_models::{GemsCollectionSet, GemsSetData, GemsSetValue, RequestBody, GEMS},
     },
     service::{
+        alarm::alarm_engine::evaluate_alarms,
+        cache::{
+            backfill::{interpolate_counter, missing_slots},
+            counter_deltas::{CounterStore, GEMS_KWH},
+        },
+        collect::collection_plan::{CollectMode, GemsEndpointPlan},
         read::read_from_addr::read_from_point_map,
         server::get_state::ServerState,
-        utils::create_time::{MINUTE, utc_now_ago},
+        task::common_scheduling::SECONDS_1MINUTE,
+        utils::create_time::slot_start,
     },
 };
 
 const READ_TIMEOUT: Duration = Duration::from_secs(60);
 
 pub async fn collection_gems_3500_modbus(state: &Arc<ServerState>) -> Result<()> {
-    let measurement_points = state.gems_measurement_point.clone();
+    let bodies = collect_gems_3500_bodies(state, CollectMode::Live).await?;
+
+    // GEMS 업로드는 꺼져 있다. 수집 / 알람 / 캐시 갱신까지만 하고 본문은 보내지 않는다
+    info!(
+        "GEMS upload disabled, {} request bodies not posted",
+        bodies.len()
+    );
+
+    Ok(())
+}
 
-    if measurement_points.is_empty() {
+// 한 주기 수집 후 건물별 RequestBody 까지 만든다 (전송은 호출한 쪽에서)
+// mode 가 DryRun 이면 상태를 바꾸지 않는다 (CollectMode 참고)
+pub async fn collect_gems_3500_bodies(
+    state: &ServerState,
+    mode: CollectMode,
+) -> Result<Vec<RequestBody>> {
+    let plans = &state.collection_plans.gems;
+
+    if plans.is_empty() {
         warn!("No GEMS measurement points found");
-        return Ok(());
+        return Ok(Vec::new());
     }
 
-    let len = measurement_points.len();
-    let gems_table = state.gems_3500_memory_map_table.clone();
-    let building_id = measurement_points[0].building_id;
-
-    let point_map: DashMap<(IpAddr, u16, u8, bool), Vec<GemsCollectionSet>> =
-        measurement_points.into_iter().try_fold(
-            DashMap::new(),
-            |map, d| -> Result<DashMap<(IpAddr, u16, u8, bool), Vec<GemsCollectionSet>>> {
-                let addrs = register_from_ch(d.channel);
-                let mut registers = Vec::new();
-
-                for u in addrs {
-                    let gems_map = gems_table
-                        .get_map(u as i16)
-                        .map_err(|e| anyhow!("Could not fetch gems_table: {}", e))?;
-                    registers.push(ModbusRegister::from(gems_map));
-                }
+    let slot = slot_start(Utc::now(), SECONDS_1MINUTE as i64);
+    let mut vec = read_gems_points(state, plans, slot).await?;
+    if mode == CollectMode::Live {
+        state.acquisition_timing.check(
+            GEMS,
+            slot,
+            SECONDS_1MINUTE as i64,
+            vec.iter().map(|r| (r.measurement_point_id, r.acquired_at)),
+        );
+    }
+    backfill_gaps(state, &mut vec);
+    apply_counter_deltas(state, &mut vec, mode);
+    if mode == CollectMode::Live
+        && let Err(e) = state.counter_store.save().await
+    {
+        error!("Could not save counter state: {:?}", e);
+    }
+
+    if mode == CollectMode::DryRun {
+        return RequestBody::per_building(GEMS, vec, |r| r.building_id)
+            .map_err(|e| anyhow!("Could not create request body: {}", e));
+    }
+
+    state.demand_tracker.apply(&mut vec);
+
+    state.latest_values.update_from_records(&vec);
+
+    let mut bodies = RequestBody::per_building(GEMS, vec, |r| r.building_id)
+        .map_err(|e| anyhow!("Could not create request body: {}", e))?;
+    for body in &mut bodies {
+        state.quality_tracker.annotate_body(&state.quality_rule_table, body);
+    }
+    evaluate_alarms(state, GEMS, &bodies);
+
+    // 끝난 집계 구간은 별도 sensor_type 본문으로 같이 보낸다
+    let rollups = state
+        .rollup_engine
+        .observe(&state.rollup_window_table, GEMS, &bodies);
+    bodies.extend(rollups);
+    Ok(bodies)
+}
+
+// 수집이 빠졌던 슬롯을 kWh 보간 레코드로 채운다 (구간 사용량 계산 전에, 시간순으로 앞에)
+// GEMS-3500 메모리 맵에는 부하 프로파일(이력) 레지스터가 없어 장치에서 다시 읽지 않는다
+fn backfill_gaps(state: &ServerState, records: &mut Vec<GemsSetData>) {
+    let counters = &state.counter_store;
+    let mut filled = Vec::new();
+    for r in records.iter() {
+        let mp = r.measurement_point_id;
+        let kwh = counters.last(mp, "kwh_sum");
+        let export = counters.last(mp, "kwh_export_sum");
+        let Some(prev_at) = kwh.or(export).map(|s| s.last_read_at()) else {
+            continue;
+        };
+
+        for slot in missing_slots(mp, prev_at, r.recorded_at, SECONDS_1MINUTE as i64) {
+            let set = GemsCollectionSet {
+                measurement_point_id: mp,
+                building_id: r.building_id,
+                modbus_register: Vec::new(),
+            };
+            let mut b = set.to_set_data(GemsSetValue::new(), slot, Utc::now());
+            b.kwh_sum = interpolate_counter(kwh, r.kwh_sum, r.recorded_at, slot);
+            b.kwh_export_sum = interpolate_counter(export, r.kwh_export_sum, r.recorded_at, slot);
+            b.interpolated = [("kwh_sum", b.kwh_sum), ("kwh_export_sum", b.kwh_export_sum)]
+                .into_iter()
+                .filter_map(|(field, v)| v.map(|_| field.to_owned()))
+                .collect();
+            if !b.interpolated.is_empty() {
+                filled.push(b);
+            }
+        }
+    }
 
-                map.entry((d.host, d.port as u16, d.unit_id, d.export_sum_status))
-                    .or_default()
-                    .push(GemsCollectionSet::new(d, registers));
-                Ok(map)
-            },
-        )?;
+    if !filled.is_empty() {
+        filled.append(records);
+        *records = filled;
+    }
+}
+
+// kWh 적산값 → 구간 사용량 (롤오버/리셋/급증 판정 포함)
+fn apply_counter_deltas(state: &ServerState, records: &mut [GemsSetData], mode: CollectMode) {
+    let counters = &state.counter_store;
+    let observe = match mode {
+        CollectMode::Live => CounterStore::observe,
+        CollectMode::DryRun => CounterStore::preview,
+    };
+    for r in records.iter_mut() {
+        let mp = r.measurement_point_id;
+        (r.kwh_sum_delta, r.kwh_sum_delta_flag) =
+            observe(counters, mp, "kwh_sum", r.kwh_sum, r.recorded_at, GEMS_KWH);
+        (r.kwh_export_sum_delta, r.kwh_export_sum_delta_flag) =
+            observe(counters, mp, "kwh_export_sum", r.kwh_export_sum, r.recorded_at, GEMS_KWH);
+    }
+}
 
-    let date = utc_now_ago(0, MINUTE);
+// 기동 시 만든 장치별 계획을 동시에 읽는다. slot 이 각 레코드의 recorded_at
+pub async fn read_gems_points(
+    state: &ServerState,
+    plans: &[GemsEndpointPlan],
+    slot: DateTime<Utc>,
+) -> Result<Vec<GemsSetData>> {
+    let len = plans.iter().map(|plan| plan.sets.len()).sum();
+    let date = slot;
     let mut futures = FuturesUnordered::new();
 
-    for (key, value) in point_map.into_iter() {
-        let date = date.clone();
-        let ip = key.0;
-        let port = key.1;
-        let unit_id = key.2;
-        let export_sum_status = key.3;
-        let data = value;
+    let capture = &state.frame_capture;
+    for plan in plans {
+        let (ip, port) = (plan.ip, plan.port);
 
         let future = async move {
             match timeout(
                 READ_TIMEOUT,
-                read_from_point_map(ip, port, unit_id, export_sum_status, data, date)
+                read_from_point_map(capture, plan, date)
             ).await {
                 Ok(Ok(result)) => Ok(result),
                 Ok(Err(e)) => {
@@ -92,20 +186,16 @@ pub async fn collection_gems_3500_modbus(state: &Arc<ServerState>) -> Result<()>
     while let Some(res) = futures.next().await {
         match res {
             Ok(set_data_list) => {
-                vec.extend(set_data_list);
+                vec.extend(set_data_list.into_iter().map(|mut d| {
+                    d.derive_metrics();
+                    d
+                }));
             }
             Err(_e) => {}
         }
     }
 
-    let body = RequestBody::from_data(GEMS, building_id, vec)
-        .map_err(|e| anyhow!("Could not create request body: {}", e))?;
-    
-    // post_axum_server_direct_data(body)
-    //     .await
-    //     .map_err(|e| anyhow!("Request failed: {:?}", e))?;
-
-    Ok(())
+    Ok(vec)
 }
 
 // Hard coding (required data type)
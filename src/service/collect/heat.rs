@@ -1,21 +1,29 @@
-use anyhow::{Context, Result, anyhow};
-use byteorder::{BigEndian, ByteOrder};
+use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
-use serde_json::to_value;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio_modbus::{
-    client::{Context as ModbusContext, Reader},
-    prelude::*,
-};
+use tokio_modbus::prelude::*;
 use tracing::{error, info};
 
 use crate::{
     model::{
         gems_3005::data_models::{HEAT, RequestBody},
-        heat::data_models::HeatData,
+        heat::data_models::{HeatData, HeatMeasurementPoint},
+        modbus::modbus_register_models::ModbusRegister,
+    },
+    service::{
+        alarm::alarm_engine::evaluate_alarms,
+        cache::{
+            backfill::{interpolate_counter, missing_slots},
+            counter_deltas::{CounterStore, HEAT_CUMULATIVE},
+        },
+        capture::frame_capture::{FrameCapture, modbus_device_key},
+        collect::collection_plan::CollectMode,
+        read::read_from_register::read_registers,
+        server::get_state::ServerState,
+        task::common_scheduling::SECONDS_5MINUTE,
+        utils::create_time::slot_start,
     },
-    service::{server::get_state::ServerState, utils::create_time::utc_now_minute},
 };
 
 use super::gems_3500_modbus::post_axum_server_direct_data;
@@ -23,88 +31,267 @@ use super::gems_3500_modbus::post_axum_server_direct_data;
 pub async fn handle_heat_data(state: Arc<ServerState>) -> Result<()> {
     let start = Instant::now();
 
+    // HTTP POST (건물마다 한 번)
+    for params in collect_heat_bodies(&state, CollectMode::Live).await? {
+        let building_id = params.building_id;
+        if let Err(e) = post_axum_server_direct_data(params).await {
+            error!(
+                "Error posting HEAT data for building {} to Axum server: {:?}",
+                building_id, e
+            );
+        } else {
+            info!(
+                "Successfully posted HEAT data for building {}: {:?}",
+                building_id,
+                start.elapsed()
+            );
+        }
+    }
+    Ok(())
+}
+
+// 한 주기 수집 후 건물별 RequestBody 까지 만든다 (전송은 호출한 쪽에서)
+// mode 가 DryRun 이면 상태를 바꾸지 않는다 (CollectMode 참고)
+pub async fn collect_heat_bodies(
+    state: &ServerState,
+    mode: CollectMode,
+) -> Result<Vec<RequestBody>> {
     let measurement_points = state.heat_measurement_point.clone();
 
     if measurement_points.is_empty() {
         info!("No heat measurement points—skipping data collection");
-        return Ok(());
+        return Ok(Vec::new());
     }
 
-    let building_id = measurement_points[0].building_id;
-
+    let registers = &state.collection_plans.heat_registers;
+    let slot = slot_start(Utc::now(), SECONDS_5MINUTE as i64);
     let mut records: Vec<HeatData> = Vec::new();
 
     for row in &measurement_points {
-        let socket_addr = format!("{}:{}", row.host, row.port).parse()?;
-
-        let mut client = tcp::connect_slave(socket_addr, Slave::from(row.unit_id)).await?;
-
-        let instant_flow = get_modbus_data(&mut client, 0x00, 2).await?;
-        let instant_heat = get_modbus_data(&mut client, 0x02, 2).await?;
-        let supply_temperature = get_modbus_data(&mut client, 0x20, 2).await?;
-        let return_temperature = get_modbus_data(&mut client, 0x22, 2).await?;
-        let cumulative_flow = get_modbus_data(&mut client, 0x70, 2).await?;
-        let cumulative_heat = get_modbus_data(&mut client, 0x76, 2).await?;
-
-        let now: DateTime<Utc> = utc_now_minute();
-
-        // println!(
-        //     "Building: {}, Measurement Point: {}, Time: {}, Instant Flow: {}, Instant Heat: {}, Supply Temp: {}, Return Temp: {}, Cumulative Flow: {}, Cumulative Heat: {}",
-        //     building_id,
-        //     row.measurement_point_id,
-        //     now,
-        //     instant_flow,
-        //     instant_heat,
-        //     supply_temperature,
-        //     return_temperature,
-        //     cumulative_flow,
-        //     cumulative_heat
-        // );
-
-        records.push(HeatData {
-            building_id,
-            measurement_point_id: row.measurement_point_id,
-            instant_flow: Some(instant_flow as f64),
-            instant_heat: Some(instant_heat as f64),
-            supply_temperature: Some(supply_temperature as f64),
-            return_temperature: Some(return_temperature as f64),
-            cumulative_flow: Some(cumulative_flow as f64),
-            cumulative_heat: Some(cumulative_heat as f64),
-            recorded_at: now,
-        })
+        // 한 대가 실패해도 나머지는 수집 (빠진 포인트는 오프라인 알람으로)
+        match read_heat_point(row, registers, &state.frame_capture, slot).await {
+            Ok(record) => records.push(record),
+            Err(e) => error!(
+                "Failed to read heat point {}: {:?}",
+                row.measurement_point_id, e
+            ),
+        }
+    }
+    if mode == CollectMode::Live {
+        state.acquisition_timing.check(
+            HEAT,
+            slot,
+            SECONDS_5MINUTE as i64,
+            records
+                .iter()
+                .map(|r| (r.measurement_point_id, r.acquired_at)),
+        );
+    }
+    backfill_gaps(state, &mut records);
+    apply_counter_deltas(state, &mut records, mode);
+    if mode == CollectMode::Live
+        && let Err(e) = state.counter_store.save().await
+    {
+        error!("Could not save counter state: {:?}", e);
     }
 
-    // HTTP POST
-    let params = RequestBody {
-        sensor_type: HEAT.to_owned(),
-        building_id,
-        data: to_value(&records).context("Failed to convert records to JSON Value")?,
-    };
+    if mode == CollectMode::DryRun {
+        return RequestBody::per_building(HEAT, records, |r| r.building_id);
+    }
+
+    state.latest_values.update_from_records(&records);
 
-    if let Err(e) = post_axum_server_direct_data(params).await {
-        error!("Error posting HEAT data to Axum server: {:?}", e);
-    } else {
-        info!("Successfully posted HEAT data: {:?}", start.elapsed());
+    let mut bodies = RequestBody::per_building(HEAT, records, |r| r.building_id)?;
+    for body in &mut bodies {
+        state
+            .quality_tracker
+            .annotate_body(&state.quality_rule_table, body);
     }
-    Ok(())
+    evaluate_alarms(state, HEAT, &bodies);
+
+    // 끝난 집계 구간은 별도 sensor_type 본문으로 같이 보낸다
+    let rollups = state
+        .rollup_engine
+        .observe(&state.rollup_window_table, HEAT, &bodies);
+    bodies.extend(rollups);
+    Ok(bodies)
 }
 
-// 레지스터에서 수집한 데이터 포맷팅
-fn data_format(reg: &[u16]) -> Result<f32> {
-    if reg.len() != 2 {
-        anyhow::bail!("Expected 2 registers, got {}", reg.len());
+// 수집이 빠졌던 슬롯을 적산유량/적산열량 보간 레코드로 채운다 (열량계 이력 레지스터는 매핑돼 있지 않음)
+fn backfill_gaps(state: &ServerState, records: &mut Vec<HeatData>) {
+    let counters = &state.counter_store;
+    let mut filled = Vec::new();
+    for r in records.iter() {
+        let mp = r.measurement_point_id;
+        let flow = counters.last(mp, "cumulative_flow");
+        let heat = counters.last(mp, "cumulative_heat");
+        let Some(prev_at) = flow.or(heat).map(|s| s.last_read_at()) else {
+            continue;
+        };
+
+        for slot in missing_slots(mp, prev_at, r.recorded_at, SECONDS_5MINUTE as i64) {
+            let cumulative_flow = interpolate_counter(flow, r.cumulative_flow, r.recorded_at, slot);
+            let cumulative_heat = interpolate_counter(heat, r.cumulative_heat, r.recorded_at, slot);
+            let interpolated: Vec<String> = [
+                ("cumulative_flow", cumulative_flow),
+                ("cumulative_heat", cumulative_heat),
+            ]
+            .into_iter()
+            .filter_map(|(field, v)| v.map(|_| field.to_owned()))
+            .collect();
+            if interpolated.is_empty() {
+                continue;
+            }
+
+            filled.push(HeatData {
+                building_id: r.building_id,
+                measurement_point_id: mp,
+                instant_flow: None,
+                instant_heat: None,
+                supply_temperature: None,
+                return_temperature: None,
+                cumulative_flow,
+                cumulative_heat,
+                cumulative_flow_delta: None,
+                cumulative_flow_delta_flag: None,
+                cumulative_heat_delta: None,
+                cumulative_heat_delta_flag: None,
+                recorded_at: slot,
+                acquired_at: Utc::now(),
+                interpolated,
+            });
+        }
+    }
+
+    if !filled.is_empty() {
+        filled.append(records);
+        *records = filled;
     }
-    let mut buf = [0u8; 4];
-    BigEndian::write_u16(&mut buf[0..2], reg[1]);
-    BigEndian::write_u16(&mut buf[2..4], reg[0]);
-    Ok(BigEndian::read_f32(&buf))
 }
 
-async fn get_modbus_data(client: &mut ModbusContext, start_addr: u16, length: u16) -> Result<f32> {
-    let regs = client
-        .read_holding_registers(start_addr, length)
-        .await?
-        .map_err(|e| anyhow!("Heat Modbus read error: {:?}", e))?;
+// 적산유량/적산열량 → 구간 사용량
+fn apply_counter_deltas(state: &ServerState, records: &mut [HeatData], mode: CollectMode) {
+    let counters = &state.counter_store;
+    let observe = match mode {
+        CollectMode::Live => CounterStore::observe,
+        CollectMode::DryRun => CounterStore::preview,
+    };
+    for r in records.iter_mut() {
+        let mp = r.measurement_point_id;
+        let at = r.recorded_at;
+        (r.cumulative_flow_delta, r.cumulative_flow_delta_flag) = observe(
+            counters,
+            mp,
+            "cumulative_flow",
+            r.cumulative_flow,
+            at,
+            HEAT_CUMULATIVE,
+        );
+        (r.cumulative_heat_delta, r.cumulative_heat_delta_flag) = observe(
+            counters,
+            mp,
+            "cumulative_heat",
+            r.cumulative_heat,
+            at,
+            HEAT_CUMULATIVE,
+        );
+    }
+}
+
+// 열량계 한 대 읽기. registers 는 기동 시 heat 메모리 맵에서 풀어 둔 HEAT_FIELDS 순서
+// recorded_at 은 예정 슬롯, acquired_at 은 실제 읽은 시각
+pub async fn read_heat_point(
+    row: &HeatMeasurementPoint,
+    registers: &[ModbusRegister],
+    capture: &FrameCapture,
+    slot: DateTime<Utc>,
+) -> Result<HeatData> {
+    let socket_addr = format!("{}:{}", row.host, row.port).parse()?;
+
+    let mut client = tcp::connect_slave(socket_addr, Slave::from(row.unit_id)).await?;
+    let device = modbus_device_key(row.host, row.port as u16, row.unit_id);
+    let values = read_registers(&mut client, registers, capture, &device)
+        .await
+        .map_err(|e| anyhow!("Heat Modbus read error: {}", e))?;
+
+    heat_record(row, &values, slot, Utc::now())
+}
+
+// 계획 순서(HEAT_FIELDS)로 읽은 값 → 포인트의 건물/측정점으로 레코드 생성
+fn heat_record(
+    row: &HeatMeasurementPoint,
+    values: &[Option<f64>],
+    slot: DateTime<Utc>,
+    acquired_at: DateTime<Utc>,
+) -> Result<HeatData> {
+    let &[
+        instant_flow,
+        instant_heat,
+        supply_temperature,
+        return_temperature,
+        cumulative_flow,
+        cumulative_heat,
+    ] = values
+    else {
+        return Err(anyhow!(
+            "Heat collection plan has {} registers",
+            values.len()
+        ));
+    };
 
-    data_format(&regs)
+    Ok(HeatData {
+        building_id: row.building_id,
+        measurement_point_id: row.measurement_point_id,
+        instant_flow,
+        instant_heat,
+        supply_temperature,
+        return_temperature,
+        cumulative_flow,
+        cumulative_heat,
+        cumulative_flow_delta: None,
+        cumulative_flow_delta_flag: None,
+        cumulative_heat_delta: None,
+        cumulative_heat_delta_flag: None,
+        recorded_at: slot,
+        acquired_at,
+        interpolated: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn point(building: u128, point: u128) -> HeatMeasurementPoint {
+        HeatMeasurementPoint {
+            building_id: Uuid::from_u128(building),
+            measurement_point_id: Uuid::from_u128(point),
+            host: "127.0.0.1".parse().unwrap(),
+            port: 502,
+            unit_id: point as u8,
+        }
+    }
+
+    #[test]
+    fn record_keeps_its_point_ids() {
+        let now = Utc::now();
+        let values = [
+            Some(1.0),
+            Some(2.0),
+            Some(45.0),
+            Some(35.0),
+            Some(100.0),
+            Some(200.0),
+        ];
+        let record = heat_record(&point(0xB, 2), &values, now, now).unwrap();
+        assert_eq!(record.building_id, Uuid::from_u128(0xB));
+        assert_eq!(record.measurement_point_id, Uuid::from_u128(2));
+    }
+
+    #[test]
+    fn wrong_register_count_is_an_error() {
+        let now = Utc::now();
+        assert!(heat_record(&point(0xA, 1), &[Some(1.0)], now, now).is_err());
+    }
 }
@@ -0,0 +1,161 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    model::{
+        gems_3005::data_models::{RequestBody, VIRTUAL},
+        virtual_point::virtual_point_models::VirtualPoint,
+    },
+    service::{
+        alarm::alarm_engine::evaluate_alarms,
+        cache::latest_values::LatestValueCache,
+        server::get_state::ServerState,
+        task::common_scheduling::SECONDS_1MINUTE,
+        utils::create_time::slot_start,
+    },
+};
+
+use super::gems_3500_modbus::post_axum_server_direct_data;
+
+pub async fn handle_virtual_points(state: Arc<ServerState>) -> Result<()> {
+    // HTTP POST (건물마다 한 번)
+    for params in collect_virtual_bodies(&state) {
+        let building_id = params.building_id;
+        if let Err(e) = post_axum_server_direct_data(params).await {
+            error!("Error posting virtual point data for building {} to Axum server: {:?}", building_id, e);
+        } else {
+            info!("Successfully posted virtual point data for building {}", building_id);
+        }
+    }
+    Ok(())
+}
+
+// 최신값 캐시로 식을 계산해 실제 포인트처럼 본문을 만든다.
+// 정의 순서대로 계산하고 바로 캐시에 넣어, 뒤의 가상 포인트가 앞의 것을 참조할 수 있다
+pub fn collect_virtual_bodies(state: &ServerState) -> Vec<RequestBody> {
+    let table = &state.virtual_point_table;
+    if table.points.is_empty() {
+        return Vec::new();
+    }
+
+    // 다른 수집과 같이 recorded_at 은 1분 슬롯, acquired_at 은 실제 계산한 시각
+    let slot = slot_start(Utc::now(), SECONDS_1MINUTE as i64);
+    let mut buildings: BTreeMap<Uuid, Vec<Value>> = BTreeMap::new();
+    let mut acquired = Vec::new();
+
+    for point in &table.points {
+        let acquired_at = Utc::now();
+        let record = virtual_record(point, &state.latest_values, slot, acquired_at);
+        state.latest_values.update_from_value(&record);
+        acquired.push((point.measurement_point_id, acquired_at));
+        buildings.entry(point.building_id).or_default().push(record);
+    }
+    state
+        .acquisition_timing
+        .check(VIRTUAL, slot, SECONDS_1MINUTE as i64, acquired.into_iter());
+
+    let mut bodies: Vec<RequestBody> = buildings
+        .into_iter()
+        .map(|(building_id, data)| RequestBody {
+            sensor_type: VIRTUAL.to_owned(),
+            building_id,
+            data: Value::Array(data),
+        })
+        .collect();
+    for body in &mut bodies {
+        state.quality_tracker.annotate_body(&state.quality_rule_table, body);
+    }
+    evaluate_alarms(state, VIRTUAL, &bodies);
+
+    let rollups = state
+        .rollup_engine
+        .observe(&state.rollup_window_table, VIRTUAL, &bodies);
+    bodies.extend(rollups);
+    bodies
+}
+
+// 가상 포인트 하나의 레코드. 입력은 slot 기준 max_age_minutes 이내의 최신값만 쓴다
+pub fn virtual_record(
+    point: &VirtualPoint,
+    latest_values: &LatestValueCache,
+    slot: DateTime<Utc>,
+    acquired_at: DateTime<Utc>,
+) -> Value {
+    let mut record = Map::new();
+    record.insert("building_id".into(), json!(point.building_id));
+    record.insert("measurement_point_id".into(), json!(point.measurement_point_id));
+    record.insert("recorded_at".into(), json!(slot));
+    record.insert("acquired_at".into(), json!(acquired_at));
+
+    for f in &point.fields {
+        let oldest = slot - Duration::minutes(f.max_age_minutes);
+        let lookup = |mp: Uuid, field: &str| {
+            // 같은 가상 포인트의 앞 필드
+            if mp == point.measurement_point_id {
+                return record.get(field).and_then(Value::as_f64);
+            }
+            latest_values
+                .get(mp, field)
+                .filter(|v| v.recorded_at >= oldest)
+                .map(|v| v.value)
+        };
+        let value = f.expr.eval(&lookup).map(|v| (v * 1e6).round() / 1e6);
+        record.insert(f.field.clone(), json!(value));
+    }
+
+    Value::Object(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::virtual_point::virtual_point_models::{Expr, VirtualField};
+    use chrono::TimeZone;
+
+    #[test]
+    fn record_uses_slot_and_acquired_at() {
+        let (input, virt) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let slot = Utc.with_ymd_and_hms(2026, 10, 19, 9, 30, 0).unwrap();
+        let acquired_at = slot + Duration::seconds(31);
+
+        let cache = LatestValueCache::default();
+        cache.update_from_value(&json!({
+            "measurement_point_id": input,
+            "recorded_at": slot - Duration::minutes(3),
+            "total_w": 1000.0,
+            "stale_w": 5.0,
+        }));
+        cache.update_from_value(&json!({
+            "measurement_point_id": input,
+            "recorded_at": slot - Duration::minutes(11),
+            "stale_w": 5.0,
+        }));
+
+        let field = |name: &str, expr: &str| VirtualField {
+            field: name.to_owned(),
+            expr: Expr::parse(expr).unwrap(),
+            max_age_minutes: 10,
+        };
+        let point = VirtualPoint {
+            building_id: Uuid::from_u128(0xA),
+            measurement_point_id: virt,
+            fields: vec![
+                field("kw", &format!("{{{}:total_w}} / 1000", input)),
+                field("double_kw", &format!("{{{}:kw}} * 2", virt)),
+                field("stale", &format!("{{{}:stale_w}}", input)),
+            ],
+        };
+
+        let record = virtual_record(&point, &cache, slot, acquired_at);
+        assert_eq!(record["recorded_at"], json!(slot));
+        assert_eq!(record["acquired_at"], json!(acquired_at));
+        assert_eq!(record["kw"], json!(1.0));
+        assert_eq!(record["double_kw"], json!(2.0));
+        assert_eq!(record["stale"], Value::Null);
+    }
+}
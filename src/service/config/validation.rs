@@ -0,0 +1,383 @@
+use anyhow::{Result, anyhow};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    model::{
+        ccm::data_models::CcmMeasurementPoint,
+        gas::data_models::GasMeasurementPoint,
+        gems_3005::data_models::GemsMeasurementPoint,
+        heat::data_models::HeatMeasurementPoint,
+        iaq::data_models::{IaqMeasurementPoint, IaqRegisterMapTable},
+        modbus::memory_map_models::{GEMS_3500, MemoryMapRegistry, MemoryMapTable},
+        sihas::data_models::SihasMeasurementPoint,
+    },
+    service::{
+        collect::gems_3500_modbus::register_from_ch,
+        read::{iaq::util_funcs::format_mac_upper, sihas::util_funcs::parse_mac},
+        utils::csv_file::open_optional_csv,
+    },
+};
+
+pub const GEMS_CSV: &str = "src/files/gems.csv";
+pub const HEAT_CSV: &str = "src/files/heat.csv";
+pub const GAS_CSV: &str = "src/files/gas.csv";
+pub const IAQ_CSV: &str = "src/files/iaq.csv";
+pub const CCM_CSV: &str = "src/files/ccm.csv";
+pub const SIHAS_CSV: &str = "src/files/sihas.csv";
+
+// 문제가 있을 때 기동 거부(strict, 기본) 또는 문제 행만 빼고 기동(skip)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    Strict,
+    Skip,
+}
+
+impl ValidationMode {
+    // CONFIG_VALIDATION=strict|skip
+    pub fn from_env() -> Result<ValidationMode> {
+        match std::env::var("CONFIG_VALIDATION").as_deref() {
+            Err(_) | Ok("") | Ok("strict") => Ok(ValidationMode::Strict),
+            Ok("skip") => Ok(ValidationMode::Skip),
+            Ok(other) => Err(anyhow!("Unknown CONFIG_VALIDATION {} (strict|skip)", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigProblem {
+    pub file: &'static str,
+    pub line: u64,
+    pub column: Option<usize>, // 1부터
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.column, &self.field) {
+            (Some(col), Some(field)) => write!(
+                f,
+                "{}:{}:{} ({}): {}",
+                self.file, self.line, col, field, self.message
+            ),
+            _ => write!(f, "{}:{}: {}", self.file, self.line, self.message),
+        }
+    }
+}
+
+// 검증을 통과한 행만 담은 측정 포인트 + 발견한 문제 목록
+#[derive(Default)]
+pub struct ValidatedPoints {
+    pub gems: Vec<GemsMeasurementPoint>,
+    pub heat: Vec<HeatMeasurementPoint>,
+    pub gas: Vec<GasMeasurementPoint>,
+    pub iaq: Vec<IaqMeasurementPoint>,
+    pub ccm: Vec<CcmMeasurementPoint>,
+    pub sihas: Vec<SihasMeasurementPoint>,
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl ValidatedPoints {
+    // strict 면 문제를 모두 로그로 남기고 에러, skip 이면 경고만 남긴다
+    pub fn enforce(self, mode: ValidationMode) -> Result<ValidatedPoints> {
+        if self.problems.is_empty() {
+            return Ok(self);
+        }
+
+        match mode {
+            ValidationMode::Strict => {
+                for p in &self.problems {
+                    error!("Invalid configuration: {}", p);
+                }
+                Err(anyhow!(
+                    "{} configuration problem(s); fix them or set CONFIG_VALIDATION=skip to start without the bad rows",
+                    self.problems.len()
+                ))
+            }
+            ValidationMode::Skip => {
+                for p in &self.problems {
+                    warn!("Skipping configuration row: {}", p);
+                }
+                Ok(self)
+            }
+        }
+    }
+
+    // 모든 센서 종류의 measurement_point_id
+    pub fn measurement_point_ids(&self) -> HashSet<Uuid> {
+        let gems = self.gems.iter().map(|p| p.measurement_point_id);
+        let heat = self.heat.iter().map(|p| p.measurement_point_id);
+        let gas = self.gas.iter().map(|p| p.measurement_point_id);
+        let iaq = self.iaq.iter().map(|p| p.measurement_point_id);
+        let ccm = self.ccm.iter().map(|p| p.measurement_point_id);
+        let sihas = self.sihas.iter().map(|p| p.measurement_point_id);
+        gems.chain(heat).chain(gas).chain(iaq).chain(ccm).chain(sihas).collect()
+    }
+}
+
+struct CsvRows<T> {
+    file: &'static str,
+    headers: Vec<String>,
+    rows: Vec<(u64, T)>,
+}
+
+impl<T> CsvRows<T> {
+    fn problem(&self, line: u64, field: &str, message: String) -> ConfigProblem {
+        ConfigProblem {
+            file: self.file,
+            line,
+            column: self.headers.iter().position(|h| h == field).map(|i| i + 1),
+            field: Some(field.to_owned()),
+            message,
+        }
+    }
+}
+
+// 행마다 역직렬화해서 실패한 행은 문제로 기록하고 건너뛴다. 파일이 없으면 빈 목록
+fn read_rows<T: DeserializeOwned>(
+    file: &'static str,
+    problems: &mut Vec<ConfigProblem>,
+) -> Result<CsvRows<T>> {
+    let Some(mut rdr) =
+        open_optional_csv(file).map_err(|e| anyhow!("Failed to read {}: {}", file, e))?
+    else {
+        return Ok(CsvRows {
+            file,
+            headers: Vec::new(),
+            rows: Vec::new(),
+        });
+    };
+
+    let headers = rdr.headers()?.clone();
+    let mut out = CsvRows {
+        file,
+        headers: headers.iter().map(str::to_owned).collect(),
+        rows: Vec::new(),
+    };
+
+    for result in rdr.records() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                problems.push(ConfigProblem {
+                    file,
+                    line: e.position().map(|p| p.line()).unwrap_or(0),
+                    column: None,
+                    field: None,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+        // 헤더 바로 다음 행은 위치가 헤더 행(1)으로 보고되므로 보정
+        let line = record.position().map(|p| p.line()).unwrap_or(0).max(2);
+
+        match record.deserialize::<T>(Some(&headers)) {
+            Ok(row) => out.rows.push((line, row)),
+            Err(e) => {
+                let index = match e.kind() {
+                    csv::ErrorKind::Deserialize { err, .. } => err.field(),
+                    _ => None,
+                };
+                let message = match e.kind() {
+                    csv::ErrorKind::Deserialize { err, .. } => err.kind().to_string(),
+                    _ => e.to_string(),
+                };
+                problems.push(ConfigProblem {
+                    file,
+                    line,
+                    column: index.map(|i| i as usize + 1),
+                    field: index.and_then(|i| headers.get(i as usize)).map(str::to_owned),
+                    message,
+                });
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+// 파일 전체에서 measurement_point_id 중복 확인. 처음 나온 위치를 함께 알려준다
+#[derive(Default)]
+struct SeenIds(HashMap<Uuid, (&'static str, u64)>);
+
+impl SeenIds {
+    fn check<T>(&mut self, rows: &CsvRows<T>, line: u64, id: Uuid) -> Option<ConfigProblem> {
+        match self.0.get(&id) {
+            Some((file, first)) => Some(rows.problem(
+                line,
+                "measurement_point_id",
+                format!("duplicate measurement_point_id {} (first used at {}:{})", id, file, first),
+            )),
+            None => {
+                self.0.insert(id, (rows.file, line));
+                None
+            }
+        }
+    }
+}
+
+fn check_port<T>(rows: &CsvRows<T>, line: u64, field: &str, port: i32) -> Option<ConfigProblem> {
+    if (1..=u16::MAX as i32).contains(&port) {
+        None
+    } else {
+        Some(rows.problem(line, field, format!("port {} is outside 1-65535", port)))
+    }
+}
+
+// 수신 프레임과 비교하는 형식은 "AA:BB:CC:DD:EE:FF"
+fn check_mac<T>(rows: &CsvRows<T>, line: u64, mac: &str) -> Option<ConfigProblem> {
+    match parse_mac(mac) {
+        Ok(bytes) if format_mac_upper(&bytes) == mac => None,
+        Ok(bytes) => Some(rows.problem(
+            line,
+            "mac",
+            format!("MAC {} should be written as {}", mac, format_mac_upper(&bytes)),
+        )),
+        Err(e) => Some(rows.problem(line, "mac", e.to_string())),
+    }
+}
+
+fn check_channel<T>(
+    rows: &CsvRows<T>,
+    line: u64,
+    channel: u16,
+    table: &MemoryMapTable,
+) -> Option<ConfigProblem> {
+    gems_channel_problem(channel, table).map(|message| rows.problem(line, "channel", message))
+}
+
+// GEMS 채널 → register_from_ch 주소가 모두 메모리 맵에 있어야 한다 (channel 0 은 ch - 1 에서 넘친다)
+// 설정 검증과 /diag/gems 가 같이 쓴다
+pub fn gems_channel_problem(channel: u16, table: &MemoryMapTable) -> Option<String> {
+    if channel == 0 {
+        return Some("channel starts at 1".to_owned());
+    }
+
+    let ch = channel as u32 - 1;
+    let highest = (2420 + ch * 64 + 61).max(9000 + ch * 4);
+    if highest > u16::MAX as u32 {
+        return Some(format!("channel {} is out of range", channel));
+    }
+
+    let missing = register_from_ch(channel)
+        .into_iter()
+        .find(|&addr| table.get(addr).is_err());
+    missing.map(|addr| {
+        format!("channel {} needs register {} which is not in the memory map", channel, addr)
+    })
+}
+
+// 측정 포인트 CSV 전체 검증. 문제가 있는 행은 결과 목록에서 빠진다
+pub fn validate_points(
+    memory_maps: &MemoryMapRegistry,
+    iaq_register_map: &IaqRegisterMapTable,
+) -> Result<ValidatedPoints> {
+    let gems_table = memory_maps.get(GEMS_3500)?;
+    let mut out = ValidatedPoints::default();
+    let mut seen = SeenIds::default();
+    let iaq_types = iaq_register_map.iaq_types();
+
+    let rows = read_rows::<GemsMeasurementPoint>(GEMS_CSV, &mut out.problems)?;
+    for (line, mp) in &rows.rows {
+        let found: Vec<ConfigProblem> = [
+            seen.check(&rows, *line, mp.measurement_point_id),
+            check_port(&rows, *line, "port", mp.port),
+            check_channel(&rows, *line, mp.channel, gems_table),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        keep_if_clean(&mut out.gems, &mut out.problems, mp, found);
+    }
+
+    let rows = read_rows::<HeatMeasurementPoint>(HEAT_CSV, &mut out.problems)?;
+    for (line, mp) in &rows.rows {
+        let found: Vec<ConfigProblem> = [
+            seen.check(&rows, *line, mp.measurement_point_id),
+            check_port(&rows, *line, "port", mp.port),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        keep_if_clean(&mut out.heat, &mut out.problems, mp, found);
+    }
+
+    let rows = read_rows::<GasMeasurementPoint>(GAS_CSV, &mut out.problems)?;
+    for (line, mp) in &rows.rows {
+        let found: Vec<ConfigProblem> = [
+            seen.check(&rows, *line, mp.measurement_point_id),
+            check_port(&rows, *line, "port", mp.port),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        keep_if_clean(&mut out.gas, &mut out.problems, mp, found);
+    }
+
+    let rows = read_rows::<IaqMeasurementPoint>(IAQ_CSV, &mut out.problems)?;
+    for (line, mp) in &rows.rows {
+        let unknown_type = (!iaq_types.contains(mp.iaq_type.as_str())).then(|| {
+            rows.problem(
+                *line,
+                "iaq_type",
+                format!("iaq_type {} is not produced by the IAQ decoder", mp.iaq_type),
+            )
+        });
+        let found: Vec<ConfigProblem> = [
+            seen.check(&rows, *line, mp.measurement_point_id),
+            check_port(&rows, *line, "port_to", mp.port_to),
+            check_mac(&rows, *line, &mp.mac),
+            unknown_type,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        keep_if_clean(&mut out.iaq, &mut out.problems, mp, found);
+    }
+
+    let rows = read_rows::<CcmMeasurementPoint>(CCM_CSV, &mut out.problems)?;
+    for (line, mp) in &rows.rows {
+        let found: Vec<ConfigProblem> = [
+            seen.check(&rows, *line, mp.measurement_point_id),
+            check_mac(&rows, *line, &mp.mac),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        keep_if_clean(&mut out.ccm, &mut out.problems, mp, found);
+    }
+
+    let rows = read_rows::<SihasMeasurementPoint>(SIHAS_CSV, &mut out.problems)?;
+    for (line, mp) in &rows.rows {
+        let found: Vec<ConfigProblem> = [
+            seen.check(&rows, *line, mp.measurement_point_id),
+            check_mac(&rows, *line, &mp.mac),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        keep_if_clean(&mut out.sihas, &mut out.problems, mp, found);
+    }
+
+    Ok(out)
+}
+
+fn keep_if_clean<T: Clone>(
+    keep: &mut Vec<T>,
+    problems: &mut Vec<ConfigProblem>,
+    row: &T,
+    found: Vec<ConfigProblem>,
+) {
+    if found.is_empty() {
+        keep.push(row.clone());
+    } else {
+        problems.extend(found);
+    }
+}
@@ -0,0 +1,243 @@
+use axum::http::StatusCode;
+use chrono::Utc;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    net::SocketAddr,
+    sync::Mutex,
+};
+use tokio::time::{Duration, timeout};
+use tokio_modbus::{
+    Slave,
+    client::{Context, Reader, Writer, tcp},
+};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    model::modbus::{
+        memory_map_models::{GAS_METER, GEMS_3500, HEAT_METER},
+        write_models::{WriteAuditEntry, WriteRequest, WriteResult, WriteTarget},
+    },
+    service::server::get_state::ServerState,
+};
+
+const AUDIT_DIR: &str = "audit";
+const AUDIT_FILE: &str = "audit/modbus-writes.log";
+const MODBUS_TIMEOUT: Duration = Duration::from_secs(5);
+// 한 요청당 최대 개수 (FC16 레지스터 123개, FC15 코일 1968개)
+const MAX_WRITE_REGISTERS: usize = 123;
+const MAX_WRITE_COILS: usize = 1968;
+
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+// measurement_point_id → 장치 접속 정보 + 프로파일
+pub fn resolve_target(state: &ServerState, id: Uuid) -> Option<WriteTarget> {
+    let gems = state
+        .gems_measurement_point
+        .iter()
+        .find(|mp| mp.measurement_point_id == id)
+        .map(|mp| (GEMS_3500, mp.host, mp.port, mp.unit_id));
+    let heat = || {
+        state
+            .heat_measurement_point
+            .iter()
+            .find(|mp| mp.measurement_point_id == id)
+            .map(|mp| (HEAT_METER, mp.host, mp.port, mp.unit_id))
+    };
+    let gas = || {
+        state
+            .gas_measurement_point
+            .iter()
+            .find(|mp| mp.measurement_point_id == id)
+            .map(|mp| (GAS_METER, mp.host, mp.port, mp.unit_id))
+    };
+
+    gems.or_else(heat)
+        .or_else(gas)
+        .map(|(profile, host, port, unit_id)| WriteTarget {
+            measurement_point_id: id,
+            profile,
+            host,
+            port: port as u16,
+            unit_id,
+        })
+}
+
+// 허용 목록 확인 → 쓰기 → 다시 읽어 검증. 결과와 상관없이 감사 로그를 남긴다.
+// operator 는 인증된 API 키에서 정해진 요청자
+pub async fn write_device(
+    state: &ServerState,
+    device_id: &str,
+    req: WriteRequest,
+    operator: &str,
+    remote_addr: SocketAddr,
+) -> Result<WriteResult, (StatusCode, String)> {
+    let target = Uuid::parse_str(device_id)
+        .ok()
+        .and_then(|id| resolve_target(state, id));
+
+    let result = match &target {
+        Some(target) => execute(state, target, &req, operator).await,
+        None => Err((
+            StatusCode::NOT_FOUND,
+            format!("Unknown device {}", device_id),
+        )),
+    };
+
+    let allowed_by = result.as_ref().ok().and_then(|r| r.allowed_by.clone());
+    let (outcome, read_back, error) = match &result {
+        Ok(r) if r.verified => ("written", r.read_back.clone(), None),
+        Ok(r) => ("verify_failed", r.read_back.clone(), None),
+        Err((code, msg)) if code.is_client_error() => ("rejected", vec![], Some(msg.clone())),
+        Err((_, msg)) => ("failed", vec![], Some(msg.clone())),
+    };
+    append_audit(&WriteAuditEntry {
+        at: Utc::now(),
+        operator: operator.to_owned(),
+        remote_addr: remote_addr.to_string(),
+        device_id: device_id.to_string(),
+        target,
+        request: req,
+        outcome: outcome.to_string(),
+        allowed_by,
+        read_back,
+        error,
+    });
+
+    result
+}
+
+async fn execute(
+    state: &ServerState,
+    target: &WriteTarget,
+    req: &WriteRequest,
+    operator: &str,
+) -> Result<WriteResult, (StatusCode, String)> {
+    let bad_request = |msg: &str| Err((StatusCode::BAD_REQUEST, msg.to_string()));
+    let count = match req.table.as_str() {
+        "holding" if !req.values.is_empty() && req.coils.is_empty() => req.values.len(),
+        "coil" if !req.coils.is_empty() && req.values.is_empty() => req.coils.len(),
+        "holding" => return bad_request("holding writes need values (and no coils)"),
+        "coil" => return bad_request("coil writes need coils (and no values)"),
+        _ => return bad_request("table must be holding or coil"),
+    };
+    if req.table == "holding" && count > MAX_WRITE_REGISTERS {
+        return bad_request("at most 123 registers per write");
+    }
+    if req.table == "coil" && count > MAX_WRITE_COILS {
+        return bad_request("at most 1968 coils per write");
+    }
+    let count = count as u16;
+
+    let Some(allowed) =
+        state
+            .write_allowlist
+            .allowing_row(target.profile, &req.table, req.address, count)
+    else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!(
+                "{} [{}, +{}) is not writable for profile {}",
+                req.table, req.address, count, target.profile
+            ),
+        ));
+    };
+
+    let bad_gateway = |e: String| (StatusCode::BAD_GATEWAY, e);
+    let addr = SocketAddr::new(target.host, target.port);
+    let mut ctx = match timeout(
+        MODBUS_TIMEOUT,
+        tcp::connect_slave(addr, Slave::from(target.unit_id)),
+    )
+    .await
+    {
+        Ok(Ok(ctx)) => ctx,
+        Ok(Err(e)) => return Err(bad_gateway(format!("Could not connect to {}: {:?}", addr, e))),
+        Err(_) => return Err(bad_gateway(format!("Connect to {} timed out", addr))),
+    };
+
+    let read_back = timeout(MODBUS_TIMEOUT, write_and_read_back(&mut ctx, req, count))
+        .await
+        .map_err(|_| bad_gateway(format!("Write to {} timed out", addr)))?
+        .map_err(bad_gateway)?;
+
+    let expected: Vec<u16> = if req.table == "holding" {
+        req.values.clone()
+    } else {
+        req.coils.iter().map(|&c| c as u16).collect()
+    };
+    let verified = read_back == expected;
+    if verified {
+        info!(
+            "{} wrote {} {}..+{} on {}",
+            operator, req.table, req.address, count, addr
+        );
+    } else {
+        error!(
+            "Read-back mismatch after write to {} {} {}: wrote {:?}, read {:?}",
+            addr, req.table, req.address, expected, read_back
+        );
+    }
+
+    Ok(WriteResult {
+        target: target.clone(),
+        allowed_by: allowed.description.clone(),
+        verified,
+        read_back,
+    })
+}
+
+async fn write_and_read_back(
+    ctx: &mut Context,
+    req: &WriteRequest,
+    count: u16,
+) -> Result<Vec<u16>, String> {
+    let err = |e| format!("{:?}", e);
+    if req.table == "holding" {
+        let written = if count == 1 {
+            ctx.write_single_register(req.address, req.values[0]).await
+        } else {
+            ctx.write_multiple_registers(req.address, &req.values).await
+        };
+        written.map_err(err)?.map_err(|e| format!("{:?}", e))?;
+        ctx.read_holding_registers(req.address, count)
+            .await
+            .map_err(err)?
+            .map_err(|e| format!("{:?}", e))
+    } else {
+        let written = if count == 1 {
+            ctx.write_single_coil(req.address, req.coils[0]).await
+        } else {
+            ctx.write_multiple_coils(req.address, &req.coils).await
+        };
+        written.map_err(err)?.map_err(|e| format!("{:?}", e))?;
+        let coils = ctx
+            .read_coils(req.address, count)
+            .await
+            .map_err(err)?
+            .map_err(|e| format!("{:?}", e))?;
+        Ok(coils.into_iter().take(count as usize).map(|c| c as u16).collect())
+    }
+}
+
+fn append_audit(entry: &WriteAuditEntry) {
+    let _guard = match AUDIT_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let line = match serde_json::to_string(entry) {
+        Ok(line) => line,
+        Err(e) => {
+            error!("Could not serialize write audit entry: {:?}", e);
+            return;
+        }
+    };
+    let res = fs::create_dir_all(AUDIT_DIR).and_then(|_| {
+        let mut file = OpenOptions::new().create(true).append(true).open(AUDIT_FILE)?;
+        writeln!(file, "{}", line)
+    });
+    if let Err(e) = res {
+        error!("Could not append write audit log: {:?} ({})", e, line);
+    }
+}
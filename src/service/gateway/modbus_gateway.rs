@@ -0,0 +1,127 @@
+use anyhow::{Result, anyhow};
+use std::{future::Future, net::SocketAddr, pin::Pin, sync::Arc};
+use tokio::net::TcpListener;
+use tokio_modbus::{
+    ExceptionCode, Request, Response, SlaveRequest,
+    server::{
+        Service,
+        tcp::{Server, accept_tcp_connection},
+    },
+};
+use tracing::{info, warn};
+
+use crate::{
+    model::modbus::gateway_map_models::GatewayRegisterMap,
+    service::server::get_state::ServerState,
+};
+
+pub const GATEWAY_ADDRESS: &str = "0.0.0.0:5020";
+
+// 값이 아직 없을 때 내보내는 워드
+const MISSING_FLOAT32: u32 = 0x7FC0_0000; // NaN
+const MISSING_INT: u32 = 0xFFFF_FFFF;
+
+// 최신값 → 레지스터 워드 (상위 워드 먼저)
+fn encode_row(row: &GatewayRegisterMap, value: Option<f64>) -> Vec<u16> {
+    let scale = row.scale.unwrap_or(1.0);
+    let bits: u32 = match (row.data_type.as_str(), value) {
+        ("FLOAT32", Some(v)) => (v as f32).to_bits(),
+        ("FLOAT32", None) => MISSING_FLOAT32,
+        (_, None) => MISSING_INT,
+        ("INT16", Some(v)) => (v * scale).round().clamp(i16::MIN as f64, i16::MAX as f64) as i16
+            as u16 as u32,
+        ("UINT16", Some(v)) => (v * scale).round().clamp(0.0, u16::MAX as f64) as u32,
+        ("INT32", Some(v)) => {
+            (v * scale).round().clamp(i32::MIN as f64, i32::MAX as f64) as i32 as u32
+        }
+        (_, Some(v)) => (v * scale).round().clamp(0.0, u32::MAX as f64) as u32,
+    };
+
+    if row.word_count() == 1 {
+        vec![bits as u16]
+    } else {
+        vec![(bits >> 16) as u16, bits as u16]
+    }
+}
+
+#[derive(Clone)]
+struct GatewayService {
+    state: Arc<ServerState>,
+}
+
+impl GatewayService {
+    // 필드 장치에 접근하지 않고 캐시만 읽는다
+    fn read(&self, addr: u16, cnt: u16) -> Result<Vec<u16>, ExceptionCode> {
+        let table = &self.state.gateway_register_map_table;
+        (0..cnt)
+            .map(|i| {
+                let a = addr
+                    .checked_add(i)
+                    .ok_or(ExceptionCode::IllegalDataAddress)?;
+                let &(idx, offset) = table
+                    .idx_address
+                    .get(&a)
+                    .ok_or(ExceptionCode::IllegalDataAddress)?;
+                let row = &table.rows[idx];
+                let value = self
+                    .state
+                    .latest_values
+                    .get(row.measurement_point_id, &row.field)
+                    .map(|v| v.value);
+                Ok(encode_row(row, value)[offset as usize])
+            })
+            .collect()
+    }
+}
+
+impl Service for GatewayService {
+    type Request = SlaveRequest<'static>;
+    type Response = Response;
+    type Exception = ExceptionCode;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, ExceptionCode>> + Send>>;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let res = match req.request {
+            Request::ReadHoldingRegisters(addr, cnt) => {
+                self.read(addr, cnt).map(Response::ReadHoldingRegisters)
+            }
+            Request::ReadInputRegisters(addr, cnt) => {
+                self.read(addr, cnt).map(Response::ReadInputRegisters)
+            }
+            _ => Err(ExceptionCode::IllegalFunction),
+        };
+        Box::pin(async move { res })
+    }
+}
+
+// BMS/SCADA 용 Modbus TCP 서버. 레지스터 맵이 비어 있으면 띄우지 않는다.
+pub async fn run_modbus_gateway(state: Arc<ServerState>) -> Result<()> {
+    if state.gateway_register_map_table.rows.is_empty() {
+        info!("No modbus gateway register map → gateway disabled");
+        return Ok(());
+    }
+
+    let addr: SocketAddr = GATEWAY_ADDRESS.parse()?;
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow!("Could not bind modbus gateway to {}: {:?}", addr, e))?;
+    info!(
+        "Modbus gateway serving {} points on {}",
+        state.gateway_register_map_table.rows.len(),
+        addr
+    );
+
+    let server = Server::new(listener);
+    let on_connected = |stream, socket_addr| {
+        let service = GatewayService {
+            state: Arc::clone(&state),
+        };
+        async move { accept_tcp_connection(stream, socket_addr, |_| Ok(Some(service.clone()))) }
+    };
+    let on_process_error = |e| warn!("Modbus gateway connection error: {:?}", e);
+
+    server
+        .serve(&on_connected, on_process_error)
+        .await
+        .map_err(|e| anyhow!("Modbus gateway failed: {:?}", e))
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+
+use crate::{
+    model::{gems_3005::data_models::CCM, iaq::data_models::Frame},
+    service::{
+        read::{
+            ccm::util_funcs::handle_ccm,
+            iaq::util_funcs::format_mac_upper,
+            udp_handler::{UdpDeviceHandler, UdpRecord},
+        },
+        server::get_state::ServerState,
+    },
+};
+
+pub const CCM_DEVICE_TYPE: u8 = 5;
+
+// 스마트콘센트
+pub struct CcmHandler;
+
+impl UdpDeviceHandler for CcmHandler {
+    fn device_type(&self) -> u8 {
+        CCM_DEVICE_TYPE
+    }
+
+    fn sensor_type(&self) -> &'static str {
+        CCM
+    }
+
+    fn handle(&self, state: &ServerState, frame: &Frame) -> Result<Vec<UdpRecord>> {
+        let mac = format_mac_upper(&frame.mac);
+        handle_ccm(state, &mac, &frame.message.registers)?
+            .into_values()
+            .map(|r| UdpRecord::new(r.building_id, r.measurement_point_id, &r))
+            .collect()
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::Utc;
+use crate::{
+    model::ccm::data_models::{CcmData, CcmRecord},
+    service::{server::get_state::ServerState, utils::create_time::utc_now_minute},
+};
+use anyhow::{Context, Result};
+
+use std::collections::HashMap;
+use tracing::warn;
+use uuid::Uuid;
+
+// 스마트콘센트 레지스터 → 값
+pub fn ccm_data(registers: &[u16]) -> Result<CcmData> {
+    if registers.len() != 64 {
+        anyhow::bail!("Expected 64 registers, got {}", registers.len());
+    }
+
+    Ok(CcmData {
+        onoff: registers[0],
+        voltage: registers[1] as f64 / 100.0,
+        current: registers[2] as f64 / 1000.0,
+        watt: registers[3] as f64 / 10.0,
+        power_factor: registers[4] as f64 / 10.0,
+        today_usage: registers[5] as f64 / 10.0,
+        this_month_usage: (registers[26] as u32) + ((registers[27] as u32) << 16),
+    })
+}
+
+// MAC 으로 매핑된 CCM 포인트에 측정값 부여
+pub fn handle_ccm(
+    state: &ServerState,
+    mac: &str,
+    registers: &[u16],
+) -> Result<HashMap<Uuid, CcmRecord>> {
+    let data = ccm_data(registers).context("Failed to convert CCM registers")?;
+
+    let now = utc_now_minute();
+    let acquired_at = Utc::now();
+    let map: HashMap<Uuid, CcmRecord> = state
+        .ccm_measurement_point
+        .iter()
+        .filter(|mp| mp.mac.eq_ignore_ascii_case(mac))
+        .map(|mp| (mp.measurement_point_id, CcmRecord::new(mp, &data, now, acquired_at)))
+        .collect();
+
+    if map.is_empty() {
+        warn!("No CCM measurement points found for MAC {}", mac);
+    }
+
+    Ok(map)
+}
@@ -0,0 +1,318 @@
+use anyhow::{Result, anyhow};
+use axum::http::StatusCode;
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Instant,
+};
+use tokio::time::{Duration, timeout};
+use tokio_modbus::{Slave, client::tcp};
+use uuid::Uuid;
+
+use crate::{
+    model::{
+        gems_3005::data_models::{GemsMeasurementPoint, GemsSetData},
+        modbus::{memory_map_models::MemoryMapTable, modbus_register_models::ModbusRegisterType},
+    },
+    service::{
+        capture::frame_capture::{FrameCapture, modbus_device_key},
+        collect::collection_plan::{GemsEndpointPlan, gems_collection_set},
+        config::validation::gems_channel_problem,
+        read::{
+            interpret_modbus_register::interpret_modbus_register_return_type,
+            read_from_addr::read_from_point_map,
+            read_from_register::{read_holding_words, read_input_words},
+        },
+        utils::create_time::utc_now_minute,
+    },
+};
+
+const MODBUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn default_port() -> u16 {
+    502
+}
+
+fn default_unit() -> u8 {
+    1
+}
+
+fn default_fc() -> u8 {
+    4
+}
+
+fn default_divide_by() -> i16 {
+    1
+}
+
+// GET /diag/modbus?host=&port=&unit=&addr=&count=&fc=&type=&divide_by=
+#[derive(Deserialize, Debug, Clone)]
+pub struct DiagReadQuery {
+    pub host: IpAddr,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default = "default_unit")]
+    pub unit: u8,
+    pub addr: u16,
+    pub count: Option<u16>,
+    #[serde(default = "default_fc")]
+    pub fc: u8, // 3 | 4
+    #[serde(rename = "type")]
+    pub value_type: Option<String>, // 메모리 맵 data_type 이름 (UINT16 | INT32 | FLOAT32_WS ...). FLOAT32 = FLOAT32_WS
+    #[serde(default = "default_divide_by")]
+    pub divide_by: i16,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DiagReadResult {
+    pub words: Vec<u16>,
+    pub value: Option<f64>,
+    pub latency_ms: f64,
+}
+
+// GET /diag/gems?host=&port=&unit=&channel=&export_sum=
+#[derive(Deserialize, Debug, Clone)]
+pub struct DiagGemsQuery {
+    pub host: IpAddr,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default = "default_unit")]
+    pub unit: u8,
+    pub channel: u16,
+    #[serde(default)]
+    pub export_sum: bool,
+}
+
+#[derive(Serialize)]
+pub struct DiagGemsResult {
+    pub data: GemsSetData,
+    pub latency_ms: f64,
+}
+
+// type 은 메모리 맵 data_type 이름 (FLOAT32 는 FLOAT32_WS 로 취급)
+fn parse_value_type(value_type: Option<&str>) -> Result<Option<ModbusRegisterType>> {
+    match value_type {
+        None => Ok(None),
+        Some("FLOAT32") => Ok(Some(ModbusRegisterType::FLOAT32_WS)),
+        Some(t) => ModbusRegisterType::from_name(t)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Unknown type {}", t)),
+    }
+}
+
+// 수집과 같은 읽기 경로로 레지스터 한 번 읽기 (원시 워드, 해석값, 왕복 시간)
+// 잘못된 요청은 400, 장치 연결/응답 문제는 502
+pub async fn diag_read(
+    query: &DiagReadQuery,
+    capture: &FrameCapture,
+) -> Result<DiagReadResult, (StatusCode, String)> {
+    let bad_request = |msg: String| (StatusCode::BAD_REQUEST, msg);
+    let bad_gateway = |msg: String| (StatusCode::BAD_GATEWAY, msg);
+
+    let value_type =
+        parse_value_type(query.value_type.as_deref()).map_err(|e| bad_request(e.to_string()))?;
+    let words_needed = value_type.as_ref().map_or(1, ModbusRegisterType::word_count);
+    let count = query.count.unwrap_or(words_needed);
+    if count == 0 || count > 125 {
+        return Err(bad_request("count must be within 1-125".to_owned()));
+    }
+    if count < words_needed {
+        return Err(bad_request(format!("type needs {} registers", words_needed)));
+    }
+    if query.addr as u32 + count as u32 > 0x1_0000 {
+        return Err(bad_request("addr + count runs past register 65535".to_owned()));
+    }
+    if query.divide_by == 0 {
+        return Err(bad_request("divide_by must not be 0".to_owned()));
+    }
+    if query.fc != 3 && query.fc != 4 {
+        return Err(bad_request(format!("Unsupported function code {}", query.fc)));
+    }
+
+    let addr = SocketAddr::new(query.host, query.port);
+    let device = modbus_device_key(query.host, query.port, query.unit);
+    let start = Instant::now();
+    let mut ctx = timeout(MODBUS_TIMEOUT, tcp::connect_slave(addr, Slave::from(query.unit)))
+        .await
+        .map_err(|_| bad_gateway(format!("Connect to {} timed out", addr)))?
+        .map_err(|e| bad_gateway(format!("Could not connect to {}: {:?}", addr, e)))?;
+
+    let read = async {
+        if query.fc == 3 {
+            read_holding_words(&mut ctx, query.addr, count, capture, &device).await
+        } else {
+            read_input_words(&mut ctx, query.addr, count, capture, &device).await
+        }
+    };
+    let words = timeout(MODBUS_TIMEOUT, read)
+        .await
+        .map_err(|_| bad_gateway(format!("Read from {} timed out", addr)))?
+        .map_err(|e| bad_gateway(e.to_string()))?;
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let value = match value_type {
+        None => None,
+        Some(value_type) => {
+            interpret_modbus_register_return_type(&words, value_type, query.divide_by)
+                .map_err(|e| bad_gateway(e.to_string()))?
+        }
+    };
+
+    Ok(DiagReadResult {
+        words,
+        value,
+        latency_ms,
+    })
+}
+
+// GEMS 채널 하나를 수집과 같은 방식으로 읽어 필드 이름 그대로 반환
+pub async fn diag_gems_channel(
+    query: &DiagGemsQuery,
+    gems_table: &MemoryMapTable,
+    capture: &FrameCapture,
+) -> Result<DiagGemsResult, (StatusCode, String)> {
+    if let Some(problem) = gems_channel_problem(query.channel, gems_table) {
+        return Err((StatusCode::BAD_REQUEST, problem));
+    }
+
+    let point = GemsMeasurementPoint {
+        building_id: Uuid::nil(),
+        measurement_point_id: Uuid::nil(),
+        host: query.host,
+        port: query.port as i32,
+        unit_id: query.unit,
+        channel: query.channel,
+        export_sum_status: query.export_sum,
+    };
+    let plan = GemsEndpointPlan {
+        ip: query.host,
+        port: query.port,
+        unit_id: query.unit,
+        sets: vec![
+            gems_collection_set(gems_table, &point)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        ],
+    };
+
+    let start = Instant::now();
+    let data = read_from_point_map(capture, &plan, utc_now_minute())
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("Could not connect to {}:{}", query.host, query.port),
+            )
+        })?;
+
+    Ok(DiagGemsResult {
+        data,
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::modbus::memory_map_models::{GEMS_3500, MemoryMapRegistry};
+
+    fn read_query() -> DiagReadQuery {
+        DiagReadQuery {
+            host: "127.0.0.1".parse().unwrap(),
+            port: 1,
+            unit: 1,
+            addr: 0,
+            count: None,
+            fc: 4,
+            value_type: None,
+            divide_by: 1,
+        }
+    }
+
+    fn gems_query(channel: u16) -> DiagGemsQuery {
+        DiagGemsQuery {
+            host: "127.0.0.1".parse().unwrap(),
+            port: 1,
+            unit: 1,
+            channel,
+            export_sum: false,
+        }
+    }
+
+    async fn read_status(query: DiagReadQuery) -> StatusCode {
+        diag_read(&query, &FrameCapture::default())
+            .await
+            .unwrap_err()
+            .0
+    }
+
+    #[tokio::test]
+    async fn invalid_read_queries_are_bad_requests() {
+        let cases = [
+            DiagReadQuery {
+                count: Some(0),
+                ..read_query()
+            },
+            DiagReadQuery {
+                count: Some(126),
+                ..read_query()
+            },
+            DiagReadQuery {
+                divide_by: 0,
+                ..read_query()
+            },
+            DiagReadQuery {
+                fc: 5,
+                ..read_query()
+            },
+            DiagReadQuery {
+                value_type: Some("UINT128".to_owned()),
+                ..read_query()
+            },
+            DiagReadQuery {
+                value_type: Some("UINT32".to_owned()),
+                count: Some(1),
+                ..read_query()
+            },
+            DiagReadQuery {
+                addr: u16::MAX,
+                count: Some(2),
+                ..read_query()
+            },
+        ];
+        for query in cases {
+            assert_eq!(
+                read_status(query.clone()).await,
+                StatusCode::BAD_REQUEST,
+                "{:?}",
+                query
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_device_is_bad_gateway() {
+        assert_eq!(read_status(read_query()).await, StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn invalid_gems_channels_are_bad_requests() {
+        let maps = MemoryMapRegistry::from_csv().unwrap();
+        let table = maps.get(GEMS_3500).unwrap();
+        for channel in [0, 1100, u16::MAX] {
+            let err = diag_gems_channel(&gems_query(channel), table, &FrameCapture::default())
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(
+                err.0,
+                StatusCode::BAD_REQUEST,
+                "channel {}: {}",
+                channel,
+                err.1
+            );
+        }
+    }
+}
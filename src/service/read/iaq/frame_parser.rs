@@ -0,0 +1,400 @@
+use anyhow::{Result, anyhow};
+use std::{collections::BTreeSet, env};
+
+use crate::{
+    model::iaq::data_models::{Frame, FrameError, Header, Message},
+    service::read::iaq::util_funcs::header_checksum,
+};
+
+// 헤더: tid(2) + src(1) + dst(1) + data_length(2) + checksum(1)
+pub const HEADER_LEN: usize = 7;
+
+// 바운드 체크를 하는 바이트 리더. 모자라면 Truncated 를 돌려준다.
+struct FrameReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> FrameReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        FrameReader { data, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], FrameError> {
+        let end = self.pos + n;
+        if end > self.data.len() {
+            return Err(FrameError::Truncated {
+                needed: end,
+                available: self.data.len(),
+            });
+        }
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, FrameError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, FrameError> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn array6(&mut self) -> Result<[u8; 6], FrameError> {
+        let mut arr = [0u8; 6];
+        arr.copy_from_slice(self.take(6)?);
+        Ok(arr)
+    }
+}
+
+// 본문 체크섬 검증 방식 (UDP_BODY_CHECKSUM)
+// 장치 프로토콜 문서에 본문 체크섬 계산법이 없어 기본은 검증하지 않는다 (값만 읽어 둔다).
+//  off   : 검증 안 함 (기본)
+//  sum16 : version ~ 마지막 레지스터 바이트 합 (u16, wrapping). udp-send 생성기가 쓰는 방식
+//          으로 임의로 정한 것이고 실제 장치 알고리즘이 아니다. 실장치 프레임에는 켜지 말 것
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyChecksumMode {
+    #[default]
+    Off,
+    Sum16,
+}
+
+impl BodyChecksumMode {
+    pub fn from_env() -> Result<BodyChecksumMode> {
+        match env::var("UDP_BODY_CHECKSUM").as_deref() {
+            Err(_) | Ok("off") => Ok(BodyChecksumMode::Off),
+            Ok("sum16") => Ok(BodyChecksumMode::Sum16),
+            Ok(other) => Err(anyhow!(
+                "UDP_BODY_CHECKSUM must be off or sum16, got {}",
+                other
+            )),
+        }
+    }
+}
+
+// 본문 체크섬 (sum16): version ~ 마지막 레지스터까지 바이트 합 (u16, wrapping)
+pub fn body_checksum(bytes: &[u8]) -> u16 {
+    bytes
+        .iter()
+        .fold(0u16, |acc, &b| acc.wrapping_add(b as u16))
+}
+
+// UDP 데이터그램 → Frame. I/O 없이 바이트만 보고 판단하는 순수 함수.
+// function_codes 에 없는 펑션코드는 FunctionCode 에러.
+pub fn parse_frame(
+    data: &[u8],
+    function_codes: &BTreeSet<u8>,
+    checksum_mode: BodyChecksumMode,
+) -> Result<Frame, FrameError> {
+    let mut rd = FrameReader::new(data);
+
+    // 1) 헤더
+    let header = Header {
+        tid: rd.u16()?,
+        src: rd.u8()?,
+        dst: rd.u8()?,
+        data_length: rd.u16()?,
+        checksum: rd.u8()?,
+    };
+
+    let expected = header_checksum(&header);
+    if expected != header.checksum {
+        return Err(FrameError::HeaderChecksum {
+            expected,
+            actual: header.checksum,
+        });
+    }
+
+    // data_length 는 헤더 뒤 바이트 수
+    let body_len = data.len() - HEADER_LEN;
+    if header.data_length as usize != body_len {
+        return Err(FrameError::LengthMismatch {
+            declared: header.data_length,
+            actual: body_len,
+        });
+    }
+
+    // 2) 펑션코드
+    let function_code = rd.u8()?;
+    if !function_codes.contains(&function_code) {
+        return Err(FrameError::FunctionCode(function_code));
+    }
+
+    // 3) 메타 정보
+    let local_addr = rd.array6()?;
+    let ssid = {
+        let raw = rd.take(32)?;
+        let trimmed: Vec<u8> = raw.iter().copied().take_while(|&b| b != 0x00).collect();
+        String::from_utf8_lossy(&trimmed).into_owned()
+    };
+    let mac = rd.array6()?;
+    let device_type = rd.u8()?;
+    let cfg = rd.u8()?;
+
+    // 4) 메시지
+    let msg_start = rd.pos;
+    let version = rd.u16()?;
+    let count = rd.u8()?;
+    let offset = rd.u16()?;
+    if offset as u32 + count as u32 > 0x1_0000 {
+        return Err(FrameError::RegisterRange { offset, count });
+    }
+    let registers = (0..count)
+        .map(|_| rd.u16())
+        .collect::<Result<Vec<u16>, FrameError>>()?;
+    let computed = body_checksum(&data[msg_start..rd.pos]);
+    let checksum = rd.u16()?;
+
+    if checksum_mode == BodyChecksumMode::Sum16 && computed != checksum {
+        return Err(FrameError::BodyChecksum {
+            expected: computed,
+            actual: checksum,
+        });
+    }
+
+    // 체크섬 뒤에 남는 바이트가 있으면 선언 길이와 실제 프레임이 맞지 않는 것
+    if rd.pos != data.len() {
+        return Err(FrameError::LengthMismatch {
+            declared: header.data_length,
+            actual: rd.pos - HEADER_LEN,
+        });
+    }
+
+    Ok(Frame {
+        header,
+        function_code,
+        local_addr,
+        ssid,
+        mac,
+        device_type,
+        cfg,
+        message: Message {
+            version,
+            count,
+            offset,
+            registers,
+            checksum,
+        },
+    })
+}
+
+// Frame → UDP 데이터그램. data_length, 헤더/본문 체크섬, count 는 내용으로부터 다시 계산한다.
+pub fn encode_frame(frame: &Frame) -> Vec<u8> {
+    let mut body = Vec::with_capacity(64 + frame.message.registers.len() * 2);
+    body.push(frame.function_code);
+    body.extend_from_slice(&frame.local_addr);
+
+    let mut ssid = [0u8; 32];
+    let ssid_bytes = frame.ssid.as_bytes();
+    let n = ssid_bytes.len().min(32);
+    ssid[..n].copy_from_slice(&ssid_bytes[..n]);
+    body.extend_from_slice(&ssid);
+
+    body.extend_from_slice(&frame.mac);
+    body.push(frame.device_type);
+    body.push(frame.cfg);
+
+    let msg_start = body.len();
+    body.extend_from_slice(&frame.message.version.to_be_bytes());
+    body.push(frame.message.registers.len() as u8);
+    body.extend_from_slice(&frame.message.offset.to_be_bytes());
+    for reg in &frame.message.registers {
+        body.extend_from_slice(&reg.to_be_bytes());
+    }
+    let checksum = body_checksum(&body[msg_start..]);
+    body.extend_from_slice(&checksum.to_be_bytes());
+
+    let mut header = Header {
+        tid: frame.header.tid,
+        src: frame.header.src,
+        dst: frame.header.dst,
+        data_length: body.len() as u16,
+        checksum: 0,
+    };
+    header.checksum = header_checksum(&header);
+
+    let mut out = Vec::with_capacity(HEADER_LEN + body.len());
+    out.extend_from_slice(&header.tid.to_be_bytes());
+    out.push(header.src);
+    out.push(header.dst);
+    out.extend_from_slice(&header.data_length.to_be_bytes());
+    out.push(header.checksum);
+    out.extend_from_slice(&body);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::iaq::data_models::Message;
+
+    const REPORT: u8 = 0x24;
+
+    fn codes() -> BTreeSet<u8> {
+        BTreeSet::from([REPORT])
+    }
+
+    fn frame(offset: u16, registers: Vec<u16>) -> Vec<u8> {
+        encode_frame(&Frame {
+            header: Header {
+                tid: 0x0102,
+                src: 1,
+                dst: 0,
+                data_length: 0,
+                checksum: 0,
+            },
+            function_code: REPORT,
+            local_addr: [10, 1, 0, 41, 0x13, 0x8D],
+            ssid: String::from("sensor-net"),
+            mac: [0xAA, 0xBB, 0xCC, 0x01, 0x02, 0x03],
+            device_type: 12,
+            cfg: 0,
+            message: Message {
+                version: 1,
+                count: registers.len() as u8,
+                offset,
+                registers,
+                checksum: 0,
+            },
+        })
+    }
+
+    // 본문을 고친 뒤 data_length / 헤더 체크섬을 다시 맞춘다 (헤더 검증은 통과하도록)
+    fn reheader(bytes: &mut [u8]) {
+        let data_length = (bytes.len() - HEADER_LEN) as u16;
+        bytes[4..6].copy_from_slice(&data_length.to_be_bytes());
+        let header = Header {
+            tid: u16::from_be_bytes([bytes[0], bytes[1]]),
+            src: bytes[2],
+            dst: bytes[3],
+            data_length,
+            checksum: 0,
+        };
+        bytes[6] = header_checksum(&header);
+    }
+
+    // HEADER_LEN + function code(1) + local_addr(6) + ssid(32) + mac(6) + type(1) + cfg(1)
+    const MESSAGE_START: usize = HEADER_LEN + 47;
+
+    #[test]
+    fn parses_valid_frame() {
+        let bytes = frame(0, vec![215, 480, 612]);
+        let parsed = parse_frame(&bytes, &codes(), BodyChecksumMode::Sum16).unwrap();
+        assert_eq!(parsed.function_code, REPORT);
+        assert_eq!(parsed.ssid, "sensor-net");
+        assert_eq!(parsed.mac, [0xAA, 0xBB, 0xCC, 0x01, 0x02, 0x03]);
+        assert_eq!(parsed.device_type, 12);
+        assert_eq!(parsed.message.version, 1);
+        assert_eq!(parsed.message.count, 3);
+        assert_eq!(parsed.message.registers, vec![215, 480, 612]);
+    }
+
+    #[test]
+    fn short_frame_is_truncated() {
+        let bytes = frame(0, vec![1, 2]);
+        let err = parse_frame(&bytes[..4], &codes(), BodyChecksumMode::Off).unwrap_err();
+        assert_eq!(
+            err,
+            FrameError::Truncated {
+                needed: 6,
+                available: 4
+            }
+        );
+    }
+
+    #[test]
+    fn body_cut_short_is_truncated() {
+        let mut bytes = frame(0, vec![1, 2, 3]);
+        bytes.truncate(MESSAGE_START + 3);
+        reheader(&mut bytes);
+        let err = parse_frame(&bytes, &codes(), BodyChecksumMode::Off).unwrap_err();
+        assert!(matches!(err, FrameError::Truncated { .. }), "{:?}", err);
+    }
+
+    #[test]
+    fn declared_length_must_match_datagram() {
+        let mut bytes = frame(0, vec![1, 2]);
+        bytes.push(0);
+        let err = parse_frame(&bytes, &codes(), BodyChecksumMode::Off).unwrap_err();
+        assert!(
+            matches!(err, FrameError::LengthMismatch { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn trailing_bytes_after_checksum_are_rejected() {
+        let mut bytes = frame(0, vec![1, 2]);
+        bytes.extend_from_slice(&[0, 0]);
+        reheader(&mut bytes);
+        let err = parse_frame(&bytes, &codes(), BodyChecksumMode::Off).unwrap_err();
+        assert!(
+            matches!(err, FrameError::LengthMismatch { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn bad_header_checksum() {
+        let mut bytes = frame(0, vec![1, 2]);
+        let expected = bytes[6];
+        bytes[6] = expected.wrapping_add(1);
+        let err = parse_frame(&bytes, &codes(), BodyChecksumMode::Off).unwrap_err();
+        assert_eq!(
+            err,
+            FrameError::HeaderChecksum {
+                expected,
+                actual: expected.wrapping_add(1)
+            }
+        );
+    }
+
+    #[test]
+    fn bad_body_checksum_only_when_enabled() {
+        let mut bytes = frame(0, vec![1, 2]);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        let err = parse_frame(&bytes, &codes(), BodyChecksumMode::Sum16).unwrap_err();
+        assert!(matches!(err, FrameError::BodyChecksum { .. }), "{:?}", err);
+
+        let parsed = parse_frame(&bytes, &codes(), BodyChecksumMode::Off).unwrap();
+        assert_eq!(parsed.message.registers, vec![1, 2]);
+    }
+
+    #[test]
+    fn unknown_function_code() {
+        let mut bytes = frame(0, vec![1]);
+        bytes[HEADER_LEN] = 0x99;
+        let err = parse_frame(&bytes, &codes(), BodyChecksumMode::Off).unwrap_err();
+        assert_eq!(err, FrameError::FunctionCode(0x99));
+    }
+
+    #[test]
+    fn count_larger_than_body_is_truncated() {
+        let mut bytes = frame(0, vec![1, 2]);
+        // version(2) 다음이 count
+        bytes[MESSAGE_START + 2] = 200;
+        let err = parse_frame(&bytes, &codes(), BodyChecksumMode::Off).unwrap_err();
+        assert!(matches!(err, FrameError::Truncated { .. }), "{:?}", err);
+    }
+
+    #[test]
+    fn offset_plus_count_overflow() {
+        let bytes = frame(0xFFFF, vec![1, 2]);
+        let err = parse_frame(&bytes, &codes(), BodyChecksumMode::Off).unwrap_err();
+        assert_eq!(
+            err,
+            FrameError::RegisterRange {
+                offset: 0xFFFF,
+                count: 2
+            }
+        );
+
+        let bytes = frame(0xFFFE, vec![1, 2]);
+        assert!(parse_frame(&bytes, &codes(), BodyChecksumMode::Off).is_ok());
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use crate::{
+    model::{gems_3005::data_models::IAQ, iaq::data_models::Frame},
+    service::{
+        read::{
+            iaq::util_funcs::{format_mac_upper, handle_iaq},
+            udp_handler::{UdpDeviceHandler, UdpRecord},
+        },
+        server::get_state::ServerState,
+    },
+};
+
+pub const IAQ_DEVICE_TYPE: u8 = 12;
+
+// 실내환경 센서
+pub struct IaqHandler;
+
+impl UdpDeviceHandler for IaqHandler {
+    fn device_type(&self) -> u8 {
+        IAQ_DEVICE_TYPE
+    }
+
+    fn sensor_type(&self) -> &'static str {
+        IAQ
+    }
+
+    fn handle(&self, state: &ServerState, frame: &Frame) -> Result<Vec<UdpRecord>> {
+        let mac = format_mac_upper(&frame.mac);
+        handle_iaq(state, &mac, frame.message.version, &frame.message.registers)?
+            .into_values()
+            .map(|r| UdpRecord::new(r.building_id, r.measurement_point_id, &r))
+            .collect()
+    }
+}
@@ -1,53 +1,29 @@
+use chrono::Utc;
 use crate::{
-    model::iaq::data_models::{CcmData, Header, IaqData},
-    service::{server::get_state::ServerState, utils::create_time::utc_now_minute},
+    model::iaq::data_models::{
+        Header, IaqData, IaqReading, IaqReadings, IaqRegisterMap,
+    },
+    service::{
+        read::interpret_modbus_register::{
+            interpret_modbus_i16, interpret_modbus_i32, interpret_modbus_u16, interpret_modbus_u32,
+        },
+        server::get_state::ServerState,
+        utils::create_time::utc_now_minute,
+    },
 };
-use anyhow::{Context, Result};
-use byteorder::{BigEndian, ReadBytesExt};
+use anyhow::{Context, Result, anyhow};
 
-use std::{
-    collections::HashMap,
-    io::{Cursor, Read},
-    sync::Arc,
-};
+use std::collections::HashMap;
 use tracing::{info, warn};
 use uuid::Uuid;
 
-// 바이트 단위 읽기 유틸
-pub fn read_u8(cur: &mut Cursor<&[u8]>) -> Result<u8> {
-    cur.read_u8().context("Failed to read u8")
-}
-
-pub fn read_u16(cur: &mut Cursor<&[u8]>) -> Result<u16> {
-    cur.read_u16::<BigEndian>()
-        .context("Failed to read u16 big-endian")
-}
-
-pub fn read_bytes(cur: &mut Cursor<&[u8]>, n: usize) -> Result<Vec<u8>> {
-    let mut buf = vec![0u8; n];
-    cur.read_exact(&mut buf)
-        .context(format!("Failed to read {} bytes", n))?;
-    Ok(buf)
-}
-
-pub fn read_str_n(cur: &mut Cursor<&[u8]>, n: usize) -> Result<String> {
-    let raw = read_bytes(cur, n)?;
-    let trimmed: Vec<u8> = raw.into_iter().take_while(|&b| b != 0x00).collect();
-    String::from_utf8(trimmed).context("Failed to decode UTF-8 string")
-}
-
-// 체크섬 검증 (헤더)
-pub fn valid_checksum(hdr: &Header) -> bool {
+// 헤더 체크섬 계산 (checksum 필드 제외 바이트 합의 하위 8비트)
+pub fn header_checksum(hdr: &Header) -> u8 {
     let mut acc: u32 = 0;
     acc += (hdr.tid >> 8) as u32 + (hdr.tid & 0xFF) as u32;
     acc += hdr.src as u32 + hdr.dst as u32;
     acc += (hdr.data_length >> 8) as u32 + (hdr.data_length & 0xFF) as u32;
-    ((acc & 0xFF) as u8) == hdr.checksum
-}
-
-// 펑션코드 검증 (0x24)
-pub fn valid_function_code(code: u8) -> bool {
-    code == 0x24
+    (acc & 0xFF) as u8
 }
 
 // MAC 바이트 배열 → "AA:BB:CC:..." 문자열
@@ -59,53 +35,74 @@ pub fn format_mac_upper(bytes: &[u8]) -> String {
         .join(":")
 }
 
-// 실내환경 데이터 가공
-pub fn aqm_data(registers: &[u16]) -> Result<HashMap<String, f64>> {
+// 실내환경 데이터 가공. 프레임 version 에 해당하는 레이아웃으로 디코딩
+pub fn aqm_data(registers: &[u16], layout: &[&IaqRegisterMap]) -> Result<IaqReadings> {
     if registers.len() != 64 {
         anyhow::bail!("Expected 64 registers, got {}", registers.len());
     }
-    let mut m = HashMap::new();
-    m.insert("temperature".to_string(), registers[0] as f64 / 10.0);
-    m.insert("humidity".to_string(), registers[1] as f64 / 10.0);
-    m.insert("co2".to_string(), registers[2] as f64);
-    m.insert("pm25".to_string(), registers[3] as f64);
-    m.insert("pm10".to_string(), registers[4] as f64);
-    m.insert("tvoc".to_string(), registers[5] as f64);
-    m.insert("illuminance".to_string(), registers[6] as f64);
+
+    let mut m = IaqReadings::new();
+    for row in layout {
+        let Some(raw) = decode_register(registers, &row.data_type, row.register, row.bit)? else {
+            warn!(iaq_type = %row.iaq_type, "Unknown IAQ data_type {}", row.data_type);
+            continue;
+        };
+
+        m.insert(
+            row.iaq_type.clone(),
+            IaqReading {
+                value: raw / row.divide_by,
+                unit: row.unit.clone(),
+            },
+        );
+    }
     Ok(m)
 }
 
-// CCM 처리 로직 호출 (예: smart plug 데이터 저장)
-pub fn ccm_data(registers: &[u16]) -> Result<CcmData> {
-    if registers.len() != 64 {
-        anyhow::bail!("Expected 64 registers, got {}", registers.len());
-    }
+// 레지스터 배열에서 data_type 에 맞게 값 하나 추출. 모르는 data_type 이면 None
+pub fn decode_register(
+    registers: &[u16],
+    data_type: &str,
+    register: usize,
+    bit: Option<u8>,
+) -> Result<Option<f64>> {
+    let raw = match data_type {
+        "INT16" => interpret_modbus_i16(register_slice(registers, register, 1)?)?,
+        "UINT16" => interpret_modbus_u16(register_slice(registers, register, 1)?)?,
+        "INT32" => interpret_modbus_i32(register_slice(registers, register, 2)?)?,
+        "UINT32" => interpret_modbus_u32(register_slice(registers, register, 2)?)?,
+        "BIT" => {
+            let word = register_slice(registers, register, 1)?[0];
+            ((word >> bit.unwrap_or(0)) & 1) as f64
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(raw))
+}
 
-    Ok(CcmData {
-        onoff: registers[0],
-        voltage: registers[1] as f64 / 100.0,
-        current: registers[2] as f64 / 1000.0,
-        watt: registers[3] as f64 / 10.0,
-        power_factor: registers[4] as f64 / 10.0,
-        today_usage: registers[5] as f64 / 10.0,
-        this_month_usage: (registers[26] as u32) + ((registers[27] as u32) << 16),
-    })
+fn register_slice(registers: &[u16], start: usize, len: usize) -> Result<&[u16]> {
+    registers
+        .get(start..start + len)
+        .ok_or_else(|| anyhow!("Register {} out of range ({} registers)", start, registers.len()))
 }
 
 // 실제 IAQ 처리 로직 호출 (예: 상태에 버퍼 쌓기 / API 전송 등)
-pub async fn handle_iaq(
-    state: Arc<ServerState>,
-    mac: String,
-    registers: Vec<u16>,
+pub fn handle_iaq(
+    state: &ServerState,
+    mac: &str,
+    version: u16,
+    registers: &[u16],
 ) -> Result<HashMap<Uuid, IaqData>> {
     // 1) 레지스터 → 값 맵
-    let data_map = aqm_data(&registers).context("Failed to convert IAQ registers to data map")?;
+    let layout = state.iaq_register_map_table.layout(version);
+    let data_map =
+        aqm_data(registers, &layout).context("Failed to convert IAQ registers to data map")?;
 
     // 2) MAC으로 매핑된 IAQ 포인트 조회
     let mappings: Vec<_> = state
         .iaq_measurement_point
         .iter()
-        .filter(|mp| mp.mac.eq_ignore_ascii_case(&mac))
+        .filter(|mp| mp.mac.eq_ignore_ascii_case(mac))
         .collect();
 
     if mappings.is_empty() {
@@ -115,19 +112,22 @@ pub async fn handle_iaq(
 
     // 3) 페이로드 생성
     let now = utc_now_minute();
+    let acquired_at = Utc::now();
     let mut map: HashMap<Uuid, IaqData> = HashMap::new();
 
     //  let building_id = mappings[0].building_id; // building_id는 모두 동일
 
     for mp in mappings {
-        if let Some(&value) = data_map.get(&mp.iaq_type) {
+        if let Some(reading) = data_map.get(&mp.iaq_type) {
             map.insert(
                 mp.measurement_point_id,
                 IaqData {
                     building_id: mp.building_id,
                     measurement_point_id: mp.measurement_point_id,
                     recorded_at: now,
-                    value: Some(value),
+                    acquired_at,
+                    value: Some(reading.value),
+                    unit: reading.unit.clone(),
                 },
             );
         }
@@ -11,6 +11,11 @@ pub fn interpret_modbus_register_return_type(
         ModbusRegisterType::UINT32 => Some(interpret_modbus_u32(data)? / divide_by as f64),
         ModbusRegisterType::INT16 => Some(interpret_modbus_i16(data)? / divide_by as f64),
         ModbusRegisterType::INT32 => Some(interpret_modbus_i32(data)? / divide_by as f64),
+        ModbusRegisterType::FLOAT32_WS => Some(interpret_modbus_f32_ws(data)? / divide_by as f64),
+        ModbusRegisterType::DEC_PAIR => Some(interpret_modbus_dec_pair(data)? / divide_by as f64),
+        ModbusRegisterType::SIGN_MAGNITUDE => {
+            Some(interpret_modbus_sign_magnitude(data)? / divide_by as f64)
+        }
         ModbusRegisterType::None => None,
     };
 
@@ -48,3 +53,31 @@ pub fn interpret_modbus_i32(data: &[u16]) -> Result<f64> {
         Err(anyhow!("interpret_modbus_u16 is meant to take a &[u16; 2]"))
     }
 }
+
+// IEEE-754 float, 하위 워드가 먼저 온다
+pub fn interpret_modbus_f32_ws(data: &[u16]) -> Result<f64> {
+    if data.len() == 2 {
+        Ok(f32::from_bits((data[1] as u32) << 16 | data[0] as u32) as f64)
+    } else {
+        Err(anyhow!("interpret_modbus_f32_ws is meant to take a &[u16; 2]"))
+    }
+}
+
+// 상위 워드 × 10000 + 하위 워드
+pub fn interpret_modbus_dec_pair(data: &[u16]) -> Result<f64> {
+    if data.len() == 2 {
+        Ok(data[0] as f64 * 10_000.0 + data[1] as f64)
+    } else {
+        Err(anyhow!("interpret_modbus_dec_pair is meant to take a &[u16; 2]"))
+    }
+}
+
+// 첫 워드 = 부호 (0 이면 +), 두 번째 워드 = 크기
+pub fn interpret_modbus_sign_magnitude(data: &[u16]) -> Result<f64> {
+    if data.len() == 2 {
+        let magnitude = data[1] as f64;
+        Ok(if data[0] == 0 { magnitude } else { -magnitude })
+    } else {
+        Err(anyhow!("interpret_modbus_sign_magnitude is meant to take a &[u16; 2]"))
+    }
+}
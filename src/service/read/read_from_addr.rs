@@ -1,9 +1,11 @@
 use super::read_from_register::read_from_register;
-use crate::model::gems_3005::data_models::{GemsCollectionSet, GemsSetData, GemsSetValue};
+use crate::service::capture::frame_capture::{FrameCapture, modbus_device_key};
+use crate::model::gems_3005::data_models::{GemsSetData, GemsSetValue};
+use crate::service::collect::collection_plan::GemsEndpointPlan;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use futures::future::join_all;
-use std::net::{IpAddr, SocketAddr};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::time::{Duration, timeout};
@@ -12,14 +14,13 @@ use tracing::error;
 
 const MODBUS_TIMEOUT: Duration = Duration::from_secs(5);
 
+// 미리 풀어 둔 계획대로 장치 하나를 읽는다 (레지스터 순서 = register_from_ch 순서)
 pub async fn read_from_point_map(
-    ip: IpAddr,
-    port: u16,
-    unit_id: u8,
-    export_sum_status: bool,
-    data: Vec<GemsCollectionSet>,
+    capture: &FrameCapture,
+    plan: &GemsEndpointPlan,
     date: DateTime<Utc>,
 ) -> Result<Vec<GemsSetData>> {
+    let (ip, port, unit_id) = (plan.ip, plan.port, plan.unit_id);
     let addr = SocketAddr::new(ip, port);
     let connect_future = tcp::connect_slave(addr, Slave::from(unit_id));
     let ctx = match timeout(MODBUS_TIMEOUT, connect_future).await {
@@ -37,9 +38,10 @@ pub async fn read_from_point_map(
         }
     };
 
-    let mut result = Vec::with_capacity(data.len());
+    let device = modbus_device_key(ip, port, unit_id);
+    let mut result = Vec::with_capacity(plan.sets.len());
 
-    for set in data.into_iter() {
+    for set in &plan.sets {
         let register_futures: Vec<_> = set
             .modbus_register
             .iter()
@@ -47,23 +49,18 @@ pub async fn read_from_point_map(
             .map(|(i, mr)| {
                 let ctx = Arc::clone(&ctx);
                 let addr_a = mr.address;
-                let value_type = mr.value_type.clone();
-                let divide_by = mr.divide_by;
+                let device = &device;
                 async move {
                     // ctx Arc<Mutex<_>> 이므로, lock 후 사용
                     let mut conn = ctx.lock().await;
-                    let v =
-                        if i == 17 && !export_sum_status {
+                    // 읽지 않는 항목(None 형식)은 장치에 요청하지 않고 None
+                    let v = match read_from_register(&mut conn, mr, capture, device).await {
+                        Ok(f) => f,
+                        Err(e) => {
+                            error!("Could not read from register at address: {} (register: {}): {:?}", addr, addr_a, e);
                             None
-                        } else {
-                            match read_from_register(&mut *conn, addr_a, value_type, divide_by).await {
-                                Ok(f) => f,
-                                Err(e) => {
-                                    error!("Could not read from register at address: {} (register: {}): {:?}", addr, addr_a, e);
-                                    None
-                                }
-                            }
-                        };
+                        }
+                    };
 
                     Ok((i, v)) as Result<(usize, Option<f64>)>
                 }
@@ -71,6 +68,7 @@ pub async fn read_from_point_map(
             .collect();
 
         let register_results = join_all(register_futures).await;
+        let acquired_at = Utc::now();
         let mut values = GemsSetValue::new();
 
         for res in register_results {
@@ -98,7 +96,7 @@ pub async fn read_from_point_map(
             }
         }
 
-        result.push(set.to_set_data(values, date));
+        result.push(set.to_set_data(values, date, acquired_at));
     }
 
     Ok(result)
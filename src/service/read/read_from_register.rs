@@ -1,30 +1,76 @@
 use anyhow::{Result, anyhow};
 use tokio_modbus::client::{Context, Reader};
 
-use crate::model::modbus::modbus_register_models::ModbusRegisterType;
+use crate::model::modbus::modbus_register_models::{ModbusRegister, ModbusRegisterType};
+use crate::service::capture::frame_capture::FrameCapture;
 use crate::service::read::interpret_modbus_register::interpret_modbus_register_return_type;
 
 // Note that modbus words are big-endian 16-bit values.
+// 메모리 맵의 fc 에 따라 FC03 / FC04 로 읽는다
 pub async fn read_from_register(
     ctx: &mut Context,
-    reg_address: u16,
-    value_type: ModbusRegisterType,
-    divide_by: i16,
+    reg: &ModbusRegister,
+    capture: &FrameCapture,
+    device: &str,
 ) -> Result<Option<f64>> {
-    let data = ctx
-        .read_input_registers(reg_address, register_count(&value_type))
+    if matches!(reg.value_type, ModbusRegisterType::None) {
+        return Ok(None);
+    }
+    let count = reg.value_type.word_count();
+    let data = match reg.fc {
+        3 => read_holding_words(ctx, reg.address, count, capture, device).await?,
+        4 => read_input_words(ctx, reg.address, count, capture, device).await?,
+        fc => return Err(anyhow!("Unsupported function code {} for register {}", fc, reg.address)),
+    };
+
+    interpret_modbus_register_return_type(&data, reg.value_type.clone(), reg.divide_by)
+}
+
+// FC04 읽기. 캡처가 켜진 장치면 요청/응답 PDU 를 기록한다.
+pub async fn read_input_words(
+    ctx: &mut Context,
+    reg_address: u16,
+    count: u16,
+    capture: &FrameCapture,
+    device: &str,
+) -> Result<Vec<u16>> {
+    let response = ctx
+        .read_input_registers(reg_address, count)
         .await
-        .map_err(|e| anyhow!("Failed to fetch data: {:?}", e))??;
+        .map_err(|e| anyhow!("Failed to fetch data: {:?}", e))?;
+    capture.capture_modbus(device, 0x04, reg_address, count, &response);
+    Ok(response?)
+}
 
-    interpret_modbus_register_return_type(&data, value_type, divide_by)
+// FC03 읽기. 캡처가 켜진 장치면 요청/응답 PDU 를 기록한다.
+pub async fn read_holding_words(
+    ctx: &mut Context,
+    reg_address: u16,
+    count: u16,
+    capture: &FrameCapture,
+    device: &str,
+) -> Result<Vec<u16>> {
+    let response = ctx
+        .read_holding_registers(reg_address, count)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch data: {:?}", e))?;
+    capture.capture_modbus(device, 0x03, reg_address, count, &response);
+    Ok(response?)
 }
 
-fn register_count(value_type: &ModbusRegisterType) -> u16 {
-    match value_type {
-        ModbusRegisterType::UINT16 => 1,
-        ModbusRegisterType::UINT32 => 2,
-        ModbusRegisterType::INT16 => 1,
-        ModbusRegisterType::INT32 => 2,
-        _ => 0,
+// 미리 풀어 둔 레지스터 목록을 같은 순서로 읽는다. 하나라도 실패하면 에러
+pub async fn read_registers(
+    ctx: &mut Context,
+    registers: &[ModbusRegister],
+    capture: &FrameCapture,
+    device: &str,
+) -> Result<Vec<Option<f64>>> {
+    let mut values = Vec::with_capacity(registers.len());
+    for reg in registers {
+        let value = read_from_register(ctx, reg, capture, device)
+            .await
+            .map_err(|e| anyhow!("register {}: {:?}", reg.address, e))?;
+        values.push(value);
     }
+    Ok(values)
 }
@@ -0,0 +1,42 @@
+use anyhow::Result;
+
+use crate::{
+    model::{iaq::data_models::Frame, sihas::data_models::SIHAS},
+    service::{
+        read::{
+            iaq::util_funcs::format_mac_upper,
+            sihas::util_funcs::handle_sihas,
+            udp_handler::{UdpDeviceHandler, UdpRecord},
+        },
+        server::get_state::ServerState,
+    },
+};
+
+// SIHAS 장치. 레이아웃은 sihas_register_map.csv 에서 device_type 별로 결정.
+pub struct SihasHandler {
+    pub device_type: u8,
+}
+
+impl UdpDeviceHandler for SihasHandler {
+    fn device_type(&self) -> u8 {
+        self.device_type
+    }
+
+    fn sensor_type(&self) -> &'static str {
+        SIHAS
+    }
+
+    fn handle(&self, state: &ServerState, frame: &Frame) -> Result<Vec<UdpRecord>> {
+        let mac = format_mac_upper(&frame.mac);
+        handle_sihas(
+            state,
+            self.device_type,
+            &mac,
+            frame.message.version,
+            &frame.message.registers,
+        )?
+        .into_values()
+        .map(|r| UdpRecord::new(r.building_id, r.measurement_point_id, &r))
+        .collect()
+    }
+}
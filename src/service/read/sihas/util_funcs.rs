@@ -0,0 +1,292 @@
+use crate::{
+    model::{
+        iaq::data_models::{Frame, Header, Message},
+        sihas::data_models::{SihasData, SihasRegisterMap},
+    },
+    service::{
+        read::iaq::{frame_parser::encode_frame, util_funcs::decode_register},
+        server::get_state::ServerState,
+        utils::create_time::utc_now_minute,
+    },
+};
+use anyhow::{Context, Result, anyhow};
+use chrono::Utc;
+use std::{collections::HashMap, env, net::SocketAddr};
+use tokio::net::UdpSocket;
+use tracing::{debug, error, warn};
+use uuid::Uuid;
+
+// 폴링 요청 펑션코드 (SIHAS_POLL_FUNCTION_CODE, 예: 0x23).
+// 장치 문서로 확인된 값이 없어 기본은 폴링하지 않는다. 장치는 보고 프레임(0x24)으로 응답한다.
+pub fn poll_function_code_from_env() -> Result<Option<u8>> {
+    let Ok(v) = env::var("SIHAS_POLL_FUNCTION_CODE") else {
+        return Ok(None);
+    };
+    let parsed = match v.strip_prefix("0x").or_else(|| v.strip_prefix("0X")) {
+        Some(hex) => u8::from_str_radix(hex, 16),
+        None => v.parse::<u8>(),
+    };
+    parsed.map(Some).map_err(|_| {
+        anyhow!(
+            "SIHAS_POLL_FUNCTION_CODE must be a u8 (e.g. 0x23), got {}",
+            v
+        )
+    })
+}
+
+// 레이아웃대로 field → (값, 단위)
+pub fn sihas_data(
+    registers: &[u16],
+    layout: &[&SihasRegisterMap],
+) -> Result<HashMap<String, (f64, Option<String>)>> {
+    let mut m = HashMap::new();
+    for row in layout {
+        let Some(raw) = decode_register(registers, &row.data_type, row.register, row.bit)? else {
+            warn!(field = %row.field, "Unknown SIHAS data_type {}", row.data_type);
+            continue;
+        };
+        m.insert(row.field.clone(), (raw / row.divide_by, row.unit.clone()));
+    }
+    Ok(m)
+}
+
+// 보고 프레임 → MAC/field 로 매핑된 포인트 레코드
+pub fn handle_sihas(
+    state: &ServerState,
+    device_type: u8,
+    mac: &str,
+    version: u16,
+    registers: &[u16],
+) -> Result<HashMap<Uuid, SihasData>> {
+    let layout = state.sihas_register_map_table.layout(device_type, version);
+    let model = layout
+        .first()
+        .map(|r| r.model.clone())
+        .ok_or_else(|| anyhow!("No SIHAS register layout for device_type {}", device_type))?;
+
+    let values = sihas_data(registers, &layout)
+        .with_context(|| format!("Failed to decode SIHAS {} registers", model))?;
+
+    let now = utc_now_minute();
+    let acquired_at = Utc::now();
+    let map: HashMap<Uuid, SihasData> = state
+        .sihas_measurement_point
+        .iter()
+        .filter(|mp| mp.mac.eq_ignore_ascii_case(mac))
+        .filter_map(|mp| {
+            let (value, unit) = values.get(&mp.field)?;
+            Some((
+                mp.measurement_point_id,
+                SihasData {
+                    building_id: mp.building_id,
+                    measurement_point_id: mp.measurement_point_id,
+                    model: model.clone(),
+                    field: mp.field.clone(),
+                    value: Some(*value),
+                    unit: unit.clone(),
+                    recorded_at: now,
+                    acquired_at,
+                },
+            ))
+        })
+        .collect();
+
+    if map.is_empty() {
+        warn!(
+            "No SIHAS measurement points found for {} MAC {}",
+            model, mac
+        );
+    }
+
+    Ok(map)
+}
+
+// 폴링 요청 프레임. 레지스터 없이 대상 MAC 만 담는다.
+pub fn build_poll_frame(tid: u16, mac: [u8; 6], function_code: u8) -> Vec<u8> {
+    encode_frame(&Frame {
+        header: Header {
+            tid,
+            src: 0,
+            dst: 0,
+            data_length: 0,
+            checksum: 0,
+        },
+        function_code,
+        local_addr: [0u8; 6],
+        ssid: String::new(),
+        mac,
+        device_type: 0,
+        cfg: 0,
+        message: Message {
+            version: 0,
+            count: 0,
+            offset: 0,
+            registers: Vec::new(),
+            checksum: 0,
+        },
+    })
+}
+
+// "AA:BB:CC:DD:EE:FF" → 바이트
+pub fn parse_mac(mac: &str) -> Result<[u8; 6]> {
+    let parts: Vec<&str> = mac.split([':', '-']).collect();
+    if parts.len() != 6 {
+        return Err(anyhow!("Invalid MAC address: {}", mac));
+    }
+    let mut out = [0u8; 6];
+    for (i, p) in parts.iter().enumerate() {
+        out[i] = u8::from_str_radix(p, 16).map_err(|_| anyhow!("Invalid MAC address: {}", mac))?;
+    }
+    Ok(out)
+}
+
+// host 가 설정된 장치에 폴링 요청 전송. 응답은 같은 소켓으로 들어와 리스너가 처리한다.
+pub async fn send_sihas_polls(state: &ServerState, socket: &UdpSocket) {
+    let Some(function_code) = state.sihas_poll_function_code else {
+        debug!("SIHAS_POLL_FUNCTION_CODE not set → skipping SIHAS polls");
+        return;
+    };
+    let mut sent: Vec<(SocketAddr, String)> = Vec::new();
+
+    for (tid, mp) in state.sihas_measurement_point.iter().enumerate() {
+        let (Some(host), Some(port)) = (mp.host, mp.port) else {
+            continue;
+        };
+        let target = SocketAddr::new(host, port);
+        // 같은 장치의 여러 field 는 한 번만 폴링
+        if sent
+            .iter()
+            .any(|(a, m)| *a == target && m.eq_ignore_ascii_case(&mp.mac))
+        {
+            continue;
+        }
+
+        let mac = match parse_mac(&mp.mac) {
+            Ok(mac) => mac,
+            Err(e) => {
+                error!(
+                    "Skipping SIHAS poll for {}: {:?}",
+                    mp.measurement_point_id, e
+                );
+                continue;
+            }
+        };
+
+        if let Err(e) = socket
+            .send_to(&build_poll_frame(tid as u16, mac, function_code), target)
+            .await
+        {
+            error!("Could not send SIHAS poll to {}: {:?}", target, e);
+        }
+        sent.push((target, mp.mac.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::sihas::data_models::SihasRegisterMapTable;
+    use crate::service::{
+        read::{
+            iaq::frame_parser::{BodyChecksumMode, parse_frame},
+            udp_handler::REPORT_FUNCTION_CODE,
+        },
+        utils::frame_log::read_frame_log,
+    };
+    use std::{collections::BTreeSet, fs, path::Path};
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sihas");
+
+    fn fixture_table() -> SihasRegisterMapTable {
+        SihasRegisterMapTable::from_path(&format!("{}/register_map.csv", FIXTURES)).unwrap()
+    }
+
+    fn expected_values(path: &Path) -> Vec<(String, f64)> {
+        let mut rdr = csv::Reader::from_path(path).unwrap();
+        rdr.deserialize().map(|r| r.unwrap()).collect()
+    }
+
+    // tests/fixtures/sihas/<name>.log 의 모든 프레임이 <name>.expected.csv 값으로 디코딩되어야 한다
+    #[test]
+    fn decodes_fixture_frames() {
+        let table = fixture_table();
+        let codes = BTreeSet::from([REPORT_FUNCTION_CODE]);
+
+        let mut checked = 0;
+        for entry in fs::read_dir(FIXTURES).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|e| e != "log") {
+                continue;
+            }
+            let expected = expected_values(&path.with_extension("expected.csv"));
+            let frames = read_frame_log(path.to_str().unwrap()).unwrap();
+            assert!(!frames.is_empty(), "{} has no frames", path.display());
+
+            for frame in frames {
+                let frame = parse_frame(&frame.bytes, &codes, BodyChecksumMode::Off)
+                    .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+                let layout = table.layout(frame.device_type, frame.message.version);
+                assert!(!layout.is_empty(), "{}: no layout", path.display());
+
+                let values = sihas_data(&frame.message.registers, &layout).unwrap();
+                for (field, value) in &expected {
+                    let (actual, _) = values
+                        .get(field)
+                        .unwrap_or_else(|| panic!("{}: missing {}", path.display(), field));
+                    assert!(
+                        (actual - value).abs() < 1e-9,
+                        "{}: {} = {}, expected {}",
+                        path.display(),
+                        field,
+                        actual,
+                        value
+                    );
+                }
+                assert_eq!(values.len(), expected.len(), "{}", path.display());
+                checked += 1;
+            }
+        }
+        assert!(checked > 0, "no SIHAS fixtures found in {}", FIXTURES);
+    }
+
+    #[test]
+    fn unknown_version_falls_back_to_default_layout() {
+        let table = fixture_table();
+        let fields: Vec<&str> = table
+            .layout(200, 7)
+            .iter()
+            .map(|r| r.field.as_str())
+            .collect();
+        assert_eq!(
+            fields,
+            ["relay_1", "relay_2", "temperature", "illuminance", "energy"]
+        );
+        assert!(table.layout(201, 0).is_empty());
+    }
+
+    #[test]
+    fn short_register_block_is_an_error() {
+        let table = fixture_table();
+        assert!(sihas_data(&[0, 0], &table.layout(200, 0)).is_err());
+    }
+
+    #[test]
+    fn shipped_register_map_loads() {
+        SihasRegisterMapTable::from_csv().unwrap();
+    }
+
+    #[test]
+    fn poll_frame_round_trips() {
+        let mac = parse_mac("00:1a:2b:3c:4d:5e").unwrap();
+        let bytes = build_poll_frame(9, mac, 0x23);
+        let frame = parse_frame(&bytes, &BTreeSet::from([0x23]), BodyChecksumMode::Sum16).unwrap();
+        assert_eq!(frame.mac, mac);
+        assert!(frame.message.registers.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_mac() {
+        assert!(parse_mac("00:1A:2B:3C:4D").is_err());
+        assert!(parse_mac("00:1A:2B:3C:4D:ZZ").is_err());
+    }
+}
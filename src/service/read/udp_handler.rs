@@ -0,0 +1,124 @@
+use anyhow::{Result, anyhow};
+use serde_derive::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+use uuid::Uuid;
+
+use crate::{
+    model::{iaq::data_models::Frame, sihas::data_models::SihasRegisterMapTable},
+    service::{
+        read::{
+            ccm::handler::CcmHandler, iaq::handler::IaqHandler, sihas::handler::SihasHandler,
+        },
+        server::get_state::ServerState,
+    },
+};
+
+// 센서 보고 프레임 펑션코드
+pub const REPORT_FUNCTION_CODE: u8 = 0x24;
+
+// 핸들러가 만들어낸 레코드 하나 (sensor_type 별로 모아서 전송). 직렬화하면 data 만 나간다
+#[derive(Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct UdpRecord {
+    #[serde(skip)]
+    pub building_id: Uuid,
+    #[serde(skip)]
+    pub measurement_point_id: Uuid,
+    pub data: serde_json::Value,
+}
+
+impl UdpRecord {
+    pub fn new<T: serde::Serialize>(
+        building_id: Uuid,
+        measurement_point_id: Uuid,
+        record: &T,
+    ) -> Result<Self> {
+        Ok(UdpRecord {
+            building_id,
+            measurement_point_id,
+            data: serde_json::to_value(record)
+                .map_err(|e| anyhow!("Failed to convert record to json: {}", e))?,
+        })
+    }
+}
+
+// UDP 장치 종류별 처리기.
+// 새 센서 계열은 이 트레잇을 구현한 모듈을 만들고 UdpHandlerRegistry::with_defaults 에 등록한다.
+// 레지스터 레이아웃만 다른 SIHAS 장치는 sihas_register_map.csv 에 행을 추가하면 된다.
+pub trait UdpDeviceHandler: Send + Sync {
+    // 헤더의 device_type
+    fn device_type(&self) -> u8;
+
+    // 처리하는 펑션코드 목록
+    fn function_codes(&self) -> &'static [u8] {
+        &[REPORT_FUNCTION_CODE]
+    }
+
+    // 업스트림 RequestBody.sensor_type
+    fn sensor_type(&self) -> &'static str;
+
+    // 레지스터 디코딩 → MAC 매핑 → 레코드 생성
+    fn handle(&self, state: &ServerState, frame: &Frame) -> Result<Vec<UdpRecord>>;
+}
+
+#[derive(Default)]
+pub struct UdpHandlerRegistry {
+    handlers: HashMap<(u8, u8), Arc<dyn UdpDeviceHandler>>,
+}
+
+impl UdpHandlerRegistry {
+    pub fn with_defaults(sihas_table: &SihasRegisterMapTable) -> Self {
+        let mut registry = UdpHandlerRegistry::default();
+        registry.register(Arc::new(IaqHandler));
+        registry.register(Arc::new(CcmHandler));
+
+        // 전용 핸들러가 없는 SIHAS device_type 은 레지스터 맵 기반 핸들러로 처리
+        for device_type in sihas_table.models().into_keys() {
+            let handler = SihasHandler { device_type };
+            if handler
+                .function_codes()
+                .iter()
+                .all(|&fc| registry.get(device_type, fc).is_none())
+            {
+                registry.register(Arc::new(handler));
+            }
+        }
+        registry
+    }
+
+    pub fn register(&mut self, handler: Arc<dyn UdpDeviceHandler>) {
+        for &fc in handler.function_codes() {
+            self.handlers
+                .insert((handler.device_type(), fc), Arc::clone(&handler));
+        }
+    }
+
+    pub fn get(&self, device_type: u8, function_code: u8) -> Option<&Arc<dyn UdpDeviceHandler>> {
+        self.handlers.get(&(device_type, function_code))
+    }
+
+    // 어느 핸들러든 처리하는 펑션코드 (파서에서 사용)
+    pub fn function_codes(&self) -> BTreeSet<u8> {
+        self.handlers.keys().map(|&(_, fc)| fc).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn records_are_posted_as_their_data() {
+        let data = json!({ "value": 21.5 });
+        let record = UdpRecord {
+            building_id: Uuid::from_u128(0xA),
+            measurement_point_id: Uuid::from_u128(1),
+            data: data.clone(),
+        };
+        assert_eq!(serde_json::to_value(&record).unwrap(), data);
+    }
+}
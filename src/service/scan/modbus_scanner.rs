@@ -0,0 +1,608 @@
+use anyhow::{Result, anyhow};
+use futures::{StreamExt, stream};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    fmt::Write as _,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use tokio::time::{Duration, timeout};
+use tokio_modbus::{
+    Request, Response, Slave,
+    client::{Client, Context, tcp},
+    slave::SlaveContext,
+};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    model::{
+        modbus::{
+            memory_map_models::{GEMS_3500, HEAT_METER, MemoryMapRegistry},
+            modbus_register_models::ModbusRegister,
+        },
+    },
+    service::{
+        capture::frame_capture::{FrameCapture, modbus_device_key},
+        collect::gems_3500_modbus::register_from_ch,
+        read::interpret_modbus_register::interpret_modbus_register_return_type,
+    },
+};
+
+// register_from_ch 결과 중 R상 전압 위치
+const GEMS_VOLTAGE_INDEX: usize = 4;
+// 열량계 판별에 읽어 볼 항목 (heat 메모리 맵)
+const HEAT_PROBE_FIELD: &str = "instant_heat";
+// 한 번에 훑을 수 있는 host × port 조합 상한
+const MAX_ENDPOINTS: usize = 256;
+// 동시에 훑는 host × port 수. 한 endpoint 안의 유닛은 연결 하나로 순서대로 읽는다
+const MAX_CONCURRENT_ENDPOINTS: usize = 8;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ScanConfig {
+    pub hosts: String, // "10.1.1.2" 또는 "10.1.1.2-20"
+    pub ports: String, // "502" 또는 "502-503"
+    pub units: String, // "1-247"
+    pub channels: u16,
+    pub timeout_ms: u64,
+    pub building_id: Option<Uuid>,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        ScanConfig {
+            hosts: String::new(),
+            ports: "502".to_owned(),
+            units: "1-247".to_owned(),
+            channels: 32,
+            timeout_ms: 300,
+            building_id: None,
+        }
+    }
+}
+
+impl ScanConfig {
+    // scan --hosts 10.1.1.2-20 [--ports 502] [--units 1-247] [--channels 32]
+    //      [--timeout-ms 300] [--building <uuid>]
+    pub fn from_args(args: &[String]) -> Result<ScanConfig> {
+        let mut config = ScanConfig::default();
+        let mut it = args.iter();
+        while let Some(flag) = it.next() {
+            let mut value = || {
+                it.next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", flag))
+            };
+            match flag.as_str() {
+                "--hosts" => config.hosts = value()?.clone(),
+                "--ports" => config.ports = value()?.clone(),
+                "--units" => config.units = value()?.clone(),
+                "--channels" => config.channels = value()?.parse()?,
+                "--timeout-ms" => config.timeout_ms = value()?.parse()?,
+                "--building" => config.building_id = Some(value()?.parse()?),
+                other => return Err(anyhow!("Unknown scan option: {}", other)),
+            }
+        }
+
+        if config.hosts.is_empty() {
+            return Err(anyhow!("scan requires --hosts"));
+        }
+        Ok(config)
+    }
+
+    // 범위 검사만 한다. 훑을 endpoint 수를 돌려준다
+    pub fn validate(&self) -> Result<usize> {
+        self.unit_ids()?;
+        Ok(self.endpoints()?.len())
+    }
+
+    fn endpoints(&self) -> Result<Vec<SocketAddr>> {
+        let hosts = parse_hosts(&self.hosts)?;
+        let ports = parse_range(&self.ports, "ports")?;
+        if hosts.len() * ports.len() > MAX_ENDPOINTS {
+            return Err(anyhow!(
+                "Scan covers {} endpoints (max {})",
+                hosts.len() * ports.len(),
+                MAX_ENDPOINTS
+            ));
+        }
+
+        Ok(hosts
+            .iter()
+            .flat_map(|&h| ports.iter().map(move |&p| SocketAddr::new(IpAddr::V4(h), p)))
+            .collect())
+    }
+
+    fn unit_ids(&self) -> Result<Vec<u8>> {
+        let units = parse_range(&self.units, "units")?;
+        if units.iter().any(|&u| u == 0 || u > 247) {
+            return Err(anyhow!("Unit ids must be within 1-247"));
+        }
+        Ok(units.into_iter().map(|u| u as u8).collect())
+    }
+}
+
+// "a.b.c.d" 또는 "a.b.c.d-e" (마지막 옥텟 범위)
+fn parse_hosts(s: &str) -> Result<Vec<Ipv4Addr>> {
+    let (base, end) = match s.split_once('-') {
+        Some((base, end)) => (base, Some(end)),
+        None => (s, None),
+    };
+    let base: Ipv4Addr = base.trim().parse()?;
+    let Some(end) = end else {
+        return Ok(vec![base]);
+    };
+
+    let [a, b, c, first] = base.octets();
+    let last: u8 = end.trim().parse()?;
+    if last < first {
+        return Err(anyhow!("Invalid host range {}", s));
+    }
+    Ok((first..=last).map(|d| Ipv4Addr::new(a, b, c, d)).collect())
+}
+
+fn parse_range(s: &str, what: &str) -> Result<Vec<u16>> {
+    let (first, last) = match s.split_once('-') {
+        Some((first, last)) => (first.trim().parse::<u16>()?, last.trim().parse::<u16>()?),
+        None => {
+            let v = s.trim().parse::<u16>()?;
+            (v, v)
+        }
+    };
+    if last < first {
+        return Err(anyhow!("Invalid {} range {}", what, s));
+    }
+    Ok((first..=last).collect())
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DeviceIdentification {
+    pub vendor_name: Option<String>,
+    pub product_code: Option<String>,
+    pub revision: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LiveChannel {
+    pub channel: u16,
+    pub r_v: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ScannedUnit {
+    pub host: IpAddr,
+    pub port: u16,
+    pub unit_id: u8,
+    pub kind: &'static str, // gems_3500 | heat | unknown
+    pub identification: Option<DeviceIdentification>,
+    pub live_channels: Vec<LiveChannel>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ScanReport {
+    pub units: Vec<ScannedUnit>,
+    pub gems_csv: String,
+    pub heat_csv: String,
+}
+
+// 응답 없음(타임아웃/전송 오류)과 예외 응답을 구분한다. 예외라도 장치는 살아 있다.
+enum Probe {
+    NoResponse,
+    Exception,
+    Ok(Response),
+}
+
+struct Endpoint<'a> {
+    addr: SocketAddr,
+    ctx: Option<Context>,
+    wait: Duration,
+    capture: &'a FrameCapture,
+}
+
+impl Endpoint<'_> {
+    async fn call(&mut self, unit_id: u8, request: Request<'static>) -> Probe {
+        if self.ctx.is_none() {
+            self.ctx = match timeout(self.wait, tcp::connect(self.addr)).await {
+                Ok(Ok(ctx)) => Some(ctx),
+                _ => return Probe::NoResponse,
+            };
+        }
+        let Some(ctx) = self.ctx.as_mut() else {
+            return Probe::NoResponse;
+        };
+
+        ctx.set_slave(Slave(unit_id));
+        let (fc, addr, count) = match &request {
+            Request::ReadInputRegisters(a, c) => (0x04, *a, *c),
+            Request::ReadHoldingRegisters(a, c) => (0x03, *a, *c),
+            _ => (0, 0, 0),
+        };
+        match timeout(self.wait, ctx.call(request)).await {
+            Ok(Ok(Ok(response))) => {
+                if fc != 0 {
+                    let words = match &response {
+                        Response::ReadInputRegisters(w) | Response::ReadHoldingRegisters(w) => {
+                            w.clone()
+                        }
+                        _ => vec![],
+                    };
+                    let device = modbus_device_key(self.addr.ip(), self.addr.port(), unit_id);
+                    self.capture.capture_modbus(&device, fc, addr, count, &Ok(words));
+                }
+                Probe::Ok(response)
+            }
+            Ok(Ok(Err(_))) => Probe::Exception,
+            // 늦게 도착한 응답이 다음 요청과 섞이지 않도록 연결을 새로 맺는다
+            _ => {
+                self.ctx = None;
+                Probe::NoResponse
+            }
+        }
+    }
+
+    async fn read_words(&mut self, unit_id: u8, request: Request<'static>) -> Option<Vec<u16>> {
+        match self.call(unit_id, request).await {
+            Probe::Ok(Response::ReadInputRegisters(w) | Response::ReadHoldingRegisters(w)) => {
+                Some(w)
+            }
+            _ => None,
+        }
+    }
+}
+
+// 주소/포트/유닛 범위를 훑어 GEMS 채널과 열량계를 찾고 gems.csv / heat.csv 초안을 만든다
+// progress 는 끝난 endpoint 수 (POST /scan 작업 상태에 노출)
+pub async fn run_scan(
+    config: &ScanConfig,
+    memory_maps: &MemoryMapRegistry,
+    capture: &FrameCapture,
+    progress: &AtomicUsize,
+) -> Result<ScanReport> {
+    let endpoints = config.endpoints()?;
+    let unit_ids = config.unit_ids()?;
+    let wait = Duration::from_millis(config.timeout_ms.max(50));
+
+    // 채널별 R상 전압 레지스터 (주소, 형식, 배율)
+    let gems_table = memory_maps.get(GEMS_3500)?;
+    let voltage: Vec<(u16, ModbusRegister)> = (1..=config.channels.max(1))
+        .map(|ch| {
+            let address = register_from_ch(ch)[GEMS_VOLTAGE_INDEX];
+            let map = gems_table
+                .get(address)
+                .map_err(|e| anyhow!("Could not fetch gems_table: {}", e))?;
+            Ok((ch, ModbusRegister::from(map)))
+        })
+        .collect::<Result<_>>()?;
+    let heat_probe = memory_maps.get(HEAT_METER)?.register(HEAT_PROBE_FIELD)?;
+
+    // 결과는 endpoint 순서를 유지한다
+    let (unit_ids, voltage, heat_probe) = (&unit_ids, &voltage, &heat_probe);
+    let found: Vec<Vec<ScannedUnit>> = stream::iter(endpoints)
+        .map(|addr| async move {
+            let units =
+                scan_endpoint(addr, unit_ids, wait, voltage, heat_probe, capture).await;
+            progress.fetch_add(1, Ordering::Relaxed);
+            units
+        })
+        .buffered(MAX_CONCURRENT_ENDPOINTS)
+        .collect()
+        .await;
+
+    let mut report = ScanReport {
+        units: found.into_iter().flatten().collect(),
+        ..ScanReport::default()
+    };
+    let building_id = config.building_id.unwrap_or_default();
+    report.gems_csv = draft_gems_csv(&report.units, building_id);
+    report.heat_csv = draft_heat_csv(&report.units, building_id);
+    Ok(report)
+}
+
+async fn scan_endpoint(
+    addr: SocketAddr,
+    unit_ids: &[u8],
+    wait: Duration,
+    voltage: &[(u16, ModbusRegister)],
+    heat_probe: &ModbusRegister,
+    capture: &FrameCapture,
+) -> Vec<ScannedUnit> {
+    let ctx = match timeout(wait, tcp::connect(addr)).await {
+        Ok(Ok(ctx)) => ctx,
+        _ => {
+            info!("Scan: {} is not reachable", addr);
+            return Vec::new();
+        }
+    };
+    let mut endpoint = Endpoint {
+        addr,
+        ctx: Some(ctx),
+        wait,
+        capture,
+    };
+
+    let mut units = Vec::new();
+    for &unit_id in unit_ids {
+        if let Some(unit) = scan_unit(&mut endpoint, unit_id, voltage, heat_probe).await {
+            info!(
+                "Scan: {} unit {} → {} ({} live channels)",
+                addr,
+                unit_id,
+                unit.kind,
+                unit.live_channels.len()
+            );
+            units.push(unit);
+        }
+    }
+    units
+}
+
+async fn scan_unit(
+    endpoint: &mut Endpoint<'_>,
+    unit_id: u8,
+    voltage: &[(u16, ModbusRegister)],
+    heat_probe: &ModbusRegister,
+) -> Option<ScannedUnit> {
+    let (_, first) = voltage.first()?;
+    let gems_probe = endpoint
+        .call(
+            unit_id,
+            Request::ReadInputRegisters(first.address, first.value_type.word_count()),
+        )
+        .await;
+
+    let mut kind = "unknown";
+    let mut live_channels = Vec::new();
+    let gems_silent = matches!(gems_probe, Probe::NoResponse);
+    match gems_probe {
+        Probe::Ok(_) => {
+            kind = "gems_3500";
+            for (channel, reg) in voltage {
+                let Some(words) = endpoint
+                    .read_words(
+                        unit_id,
+                        Request::ReadInputRegisters(reg.address, reg.value_type.word_count()),
+                    )
+                    .await
+                else {
+                    break;
+                };
+                match interpret_modbus_register_return_type(
+                    &words,
+                    reg.value_type.clone(),
+                    reg.divide_by,
+                ) {
+                    Ok(Some(r_v)) if r_v != 0.0 => live_channels.push(LiveChannel {
+                        channel: *channel,
+                        r_v,
+                    }),
+                    Ok(_) => {}
+                    Err(e) => warn!("Scan: could not decode channel {}: {:?}", channel, e),
+                }
+            }
+        }
+        // FC04 를 무시하고 응답하지 않는 열량계도 있어 타임아웃이어도 열량계 읽기를 시도한다
+        Probe::NoResponse | Probe::Exception => {
+            let heat = endpoint
+                .call(
+                    unit_id,
+                    Request::ReadHoldingRegisters(
+                        heat_probe.address,
+                        heat_probe.value_type.word_count(),
+                    ),
+                )
+                .await;
+            match heat {
+                Probe::Ok(Response::ReadHoldingRegisters(words))
+                    if interpret_modbus_register_return_type(
+                        &words,
+                        heat_probe.value_type.clone(),
+                        heat_probe.divide_by,
+                    )
+                    .is_ok() =>
+                {
+                    kind = "heat";
+                }
+                // 두 읽기 모두 응답이 없으면 비어 있는 유닛
+                Probe::NoResponse if gems_silent => return None,
+                _ => {}
+            }
+        }
+    }
+
+    let identification = read_identification(endpoint, unit_id).await;
+
+    Some(ScannedUnit {
+        host: endpoint.addr.ip(),
+        port: endpoint.addr.port(),
+        unit_id,
+        kind,
+        identification,
+        live_channels,
+    })
+}
+
+// FC43 / MEI 14 Read Device Identification (basic: 0 vendor, 1 product code, 2 revision)
+async fn read_identification(endpoint: &mut Endpoint<'_>, unit_id: u8) -> Option<DeviceIdentification> {
+    let request = Request::Custom(0x2B, vec![0x0E, 0x01, 0x00].into());
+    let Probe::Ok(Response::Custom(0x2B, bytes)) = endpoint.call(unit_id, request).await else {
+        return None;
+    };
+    parse_identification(&bytes)
+}
+
+fn parse_identification(bytes: &[u8]) -> Option<DeviceIdentification> {
+    // MEI type, read code, conformity, more follows, next id, object 수
+    if bytes.len() < 6 || bytes[0] != 0x0E {
+        return None;
+    }
+    let mut ident = DeviceIdentification::default();
+    let mut pos = 6;
+    for _ in 0..bytes[5] {
+        let id = *bytes.get(pos)?;
+        let len = *bytes.get(pos + 1)? as usize;
+        let value = bytes.get(pos + 2..pos + 2 + len)?;
+        let value = Some(String::from_utf8_lossy(value).trim().to_owned());
+        match id {
+            0 => ident.vendor_name = value,
+            1 => ident.product_code = value,
+            2 => ident.revision = value,
+            _ => {}
+        }
+        pos += 2 + len;
+    }
+    Some(ident)
+}
+
+fn draft_gems_csv(units: &[ScannedUnit], building_id: Uuid) -> String {
+    let mut csv = "building_id,measurement_point_id,host,port,unit_id,channel,export_sum_status\n"
+        .to_owned();
+    for unit in units.iter().filter(|u| u.kind == "gems_3500") {
+        for ch in &unit.live_channels {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},false",
+                building_id,
+                Uuid::new_v4(),
+                unit.host,
+                unit.port,
+                unit.unit_id,
+                ch.channel
+            );
+        }
+    }
+    csv
+}
+
+fn draft_heat_csv(units: &[ScannedUnit], building_id: Uuid) -> String {
+    let mut csv = "building_id,measurement_point_id,host,port,unit_id,비고\n".to_owned();
+    for unit in units.iter().filter(|u| u.kind == "heat") {
+        let note = unit
+            .identification
+            .as_ref()
+            .and_then(|i| i.product_code.clone())
+            .unwrap_or_else(|| "scan".to_owned());
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{}",
+            building_id,
+            Uuid::new_v4(),
+            unit.host,
+            unit.port,
+            unit.unit_id,
+            note
+        );
+    }
+    csv
+}
+
+// CLI: 결과 요약은 로그로, csv 초안은 stdout 으로
+pub async fn run_scan_cli(config: ScanConfig) -> Result<()> {
+    let memory_maps = MemoryMapRegistry::from_csv()?;
+    let capture = FrameCapture::default();
+    let report = run_scan(&config, &memory_maps, &capture, &AtomicUsize::new(0)).await?;
+
+    info!("Scan finished: {} responding units", report.units.len());
+    println!("# gems.csv\n{}", report.gems_csv);
+    println!("# heat.csv\n{}", report.heat_csv);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{future::Future, pin::Pin};
+    use tokio::net::TcpListener;
+    use tokio_modbus::{
+        ExceptionCode, SlaveRequest,
+        server::{
+            Service,
+            tcp::{Server, accept_tcp_connection},
+        },
+    };
+
+    // FC04 에는 답하지 않고 FC03 에만 답하는 열량계
+    #[derive(Clone)]
+    struct SilentInputHeatMeter;
+
+    impl Service for SilentInputHeatMeter {
+        type Request = SlaveRequest<'static>;
+        type Response = Option<Response>;
+        type Exception = ExceptionCode;
+        type Future = Pin<Box<dyn Future<Output = Result<Option<Response>, ExceptionCode>> + Send>>;
+
+        fn call(&self, req: Self::Request) -> Self::Future {
+            Box::pin(async move {
+                match req.request {
+                    Request::ReadInputRegisters(..) => {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        Err(ExceptionCode::ServerDeviceBusy)
+                    }
+                    Request::ReadHoldingRegisters(_, cnt) => {
+                        Ok(Some(Response::ReadHoldingRegisters(vec![0; cnt as usize])))
+                    }
+                    _ => Err(ExceptionCode::IllegalFunction),
+                }
+            })
+        }
+    }
+
+    async fn spawn_meter() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let on_connected = |stream, socket_addr| async move {
+                accept_tcp_connection(stream, socket_addr, |_| Ok(Some(SilentInputHeatMeter)))
+            };
+            let _ = Server::new(listener).serve(&on_connected, |_| {}).await;
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn detects_heat_meter_when_gems_probe_times_out() {
+        let addr = spawn_meter().await;
+        let config = ScanConfig {
+            hosts: addr.ip().to_string(),
+            ports: addr.port().to_string(),
+            units: "1".to_owned(),
+            channels: 1,
+            timeout_ms: 100,
+            building_id: None,
+        };
+        let progress = AtomicUsize::new(0);
+        let report = run_scan(
+            &config,
+            &MemoryMapRegistry::from_csv().unwrap(),
+            &FrameCapture::default(),
+            &progress,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.units.len(), 1);
+        assert_eq!(report.units[0].kind, "heat");
+        assert_eq!(report.heat_csv.lines().count(), 2);
+        assert_eq!(progress.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn validate_rejects_bad_ranges() {
+        let config = |hosts: &str, units: &str| ScanConfig {
+            hosts: hosts.to_owned(),
+            units: units.to_owned(),
+            ..ScanConfig::default()
+        };
+        assert_eq!(config("10.0.0.1-4", "1-247").validate().unwrap(), 4);
+        assert!(config("10.0.0.9-1", "1").validate().is_err());
+        assert!(config("10.0.0.1", "0-3").validate().is_err());
+        assert!(config("10.0.0.1", "1-248").validate().is_err());
+        assert!(
+            ScanConfig {
+                ports: "1-1000".to_owned(),
+                ..config("10.0.0.1", "1")
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde_derive::Serialize;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use uuid::Uuid;
+
+use crate::service::scan::modbus_scanner::ScanReport;
+
+// 동시에 돌 수 있는 스캔 작업 수
+const MAX_RUNNING_SCANS: usize = 2;
+// 끝난 작업은 최근 것만 남긴다
+const MAX_KEPT_SCANS: usize = 20;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanStatus {
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ScanJob {
+    pub id: Uuid,
+    pub status: ScanStatus,
+    pub requested_by: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub endpoints_total: usize,
+    pub endpoints_done: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<ScanReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct ScanEntry {
+    job: ScanJob,
+    progress: Arc<AtomicUsize>,
+}
+
+// POST /scan 작업 목록. 메모리에만 있어 재시작하면 사라진다
+pub struct ScanJobs {
+    jobs: DashMap<Uuid, ScanEntry>,
+    running: Arc<Semaphore>,
+}
+
+impl Default for ScanJobs {
+    fn default() -> Self {
+        ScanJobs {
+            jobs: DashMap::new(),
+            running: Arc::new(Semaphore::new(MAX_RUNNING_SCANS)),
+        }
+    }
+}
+
+impl ScanJobs {
+    // 빈 자리가 없으면 None. 허가는 작업이 끝날 때까지 들고 있는다
+    pub fn try_reserve(&self) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.running).try_acquire_owned().ok()
+    }
+
+    // 새 작업을 등록하고 (작업 스냅샷, 진행 카운터) 를 돌려준다
+    pub fn start(&self, requested_by: &str, endpoints_total: usize) -> (ScanJob, Arc<AtomicUsize>) {
+        self.prune();
+        let job = ScanJob {
+            id: Uuid::new_v4(),
+            status: ScanStatus::Running,
+            requested_by: requested_by.to_owned(),
+            started_at: Utc::now(),
+            finished_at: None,
+            endpoints_total,
+            endpoints_done: 0,
+            report: None,
+            error: None,
+        };
+        let progress = Arc::new(AtomicUsize::new(0));
+        self.jobs.insert(
+            job.id,
+            ScanEntry {
+                job: job.clone(),
+                progress: Arc::clone(&progress),
+            },
+        );
+        (job, progress)
+    }
+
+    pub fn finish(&self, id: Uuid, result: anyhow::Result<ScanReport>) {
+        let Some(mut entry) = self.jobs.get_mut(&id) else {
+            return;
+        };
+        entry.job.finished_at = Some(Utc::now());
+        match result {
+            Ok(report) => {
+                entry.job.status = ScanStatus::Done;
+                entry.job.report = Some(report);
+            }
+            Err(e) => {
+                entry.job.status = ScanStatus::Failed;
+                entry.job.error = Some(e.to_string());
+            }
+        }
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<ScanJob> {
+        self.jobs.get(&id).map(|entry| {
+            let mut job = entry.job.clone();
+            job.endpoints_done = entry.progress.load(Ordering::Relaxed);
+            job
+        })
+    }
+
+    // 끝난 작업이 MAX_KEPT_SCANS 를 넘으면 오래된 것부터 지운다
+    fn prune(&self) {
+        let mut finished: Vec<(DateTime<Utc>, Uuid)> = self
+            .jobs
+            .iter()
+            .filter_map(|e| e.job.finished_at.map(|at| (at, e.job.id)))
+            .collect();
+        if finished.len() < MAX_KEPT_SCANS {
+            return;
+        }
+        finished.sort();
+        for (_, id) in &finished[..=finished.len() - MAX_KEPT_SCANS] {
+            self.jobs.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn tracks_progress_and_result() {
+        let jobs = ScanJobs::default();
+        let (job, progress) = jobs.start("ops", 3);
+        assert_eq!(job.status, ScanStatus::Running);
+
+        progress.fetch_add(2, Ordering::Relaxed);
+        assert_eq!(jobs.get(job.id).unwrap().endpoints_done, 2);
+
+        jobs.finish(job.id, Ok(ScanReport::default()));
+        let done = jobs.get(job.id).unwrap();
+        assert_eq!(done.status, ScanStatus::Done);
+        assert!(done.report.is_some() && done.finished_at.is_some());
+
+        let (failed, _) = jobs.start("ops", 1);
+        jobs.finish(failed.id, Err(anyhow!("boom")));
+        assert_eq!(jobs.get(failed.id).unwrap().error.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn limits_running_scans() {
+        let jobs = ScanJobs::default();
+        let permits: Vec<_> = (0..MAX_RUNNING_SCANS)
+            .map(|_| jobs.try_reserve().unwrap())
+            .collect();
+        assert!(jobs.try_reserve().is_none());
+        drop(permits);
+        assert!(jobs.try_reserve().is_some());
+    }
+
+    #[test]
+    fn keeps_only_recent_finished_jobs() {
+        let jobs = ScanJobs::default();
+        for _ in 0..MAX_KEPT_SCANS + 5 {
+            let (job, _) = jobs.start("ops", 1);
+            jobs.finish(job.id, Ok(ScanReport::default()));
+        }
+        let (running, _) = jobs.start("ops", 1);
+
+        assert!(jobs.get(running.id).is_some());
+        assert!(jobs.jobs.len() <= MAX_KEPT_SCANS);
+    }
+}
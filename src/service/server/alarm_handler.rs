@@ -0,0 +1,45 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde_derive::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    model::alarm::alarm_models::Alarm,
+    service::{
+        alarm::alarm_engine::notify,
+        server::{auth::Operator, get_state::ServerState},
+    },
+};
+
+#[derive(Deserialize)]
+pub struct AlarmQuery {
+    // active (기본) | all
+    pub status: Option<String>,
+}
+
+// GET /alarms?status=active|all
+pub async fn get_alarms(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<AlarmQuery>,
+) -> Json<Vec<Alarm>> {
+    let all = query.status.as_deref() == Some("all");
+    Json(state.alarm_engine.list(all))
+}
+
+// POST /alarms/{id}/ack
+pub async fn acknowledge_alarm(
+    State(state): State<Arc<ServerState>>,
+    Extension(Operator(operator)): Extension<Operator>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Alarm>, StatusCode> {
+    let Some(event) = state.alarm_engine.acknowledge(id, &operator) else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    let alarm = event.alarm.clone();
+    notify(&state.alarm_webhooks, vec![event]);
+    Ok(Json(alarm))
+}
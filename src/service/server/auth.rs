@@ -0,0 +1,43 @@
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::service::server::get_state::ServerState;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+// 인증된 요청자 (api_keys.csv 의 operator). 제어 핸들러는 Extension 으로 받는다
+#[derive(Clone, Debug)]
+pub struct Operator(pub String);
+
+// 제어 라우트 앞단: x-api-key 가 없거나 모르는 키면 401
+pub async fn require_api_key(
+    State(state): State<Arc<ServerState>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let key = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let Some(operator) = state.api_keys.operator_for(key) else {
+        warn!("Rejected {} {}: missing or unknown API key", req.method(), req.uri().path());
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "missing or unknown x-api-key" })),
+        )
+            .into_response();
+    };
+
+    let operator = Operator(operator.to_owned());
+    req.extensions_mut().insert(operator);
+    next.run(req).await
+}
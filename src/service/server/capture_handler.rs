@@ -0,0 +1,56 @@
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde_derive::Deserialize;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::service::{
+    capture::frame_capture::CaptureStatus,
+    server::{auth::Operator, get_state::ServerState},
+};
+
+#[derive(Deserialize)]
+pub struct CaptureQuery {
+    // UDP: MAC 또는 송신 IP, Modbus: "ip:port/unit"
+    pub device: String,
+}
+
+// GET /capture
+pub async fn get_capture(State(state): State<Arc<ServerState>>) -> Json<CaptureStatus> {
+    Json(state.frame_capture.status())
+}
+
+// PUT /capture?device=...
+pub async fn enable_capture(
+    State(state): State<Arc<ServerState>>,
+    Extension(Operator(operator)): Extension<Operator>,
+    Query(query): Query<CaptureQuery>,
+) -> (StatusCode, Json<CaptureStatus>) {
+    let created = state.frame_capture.enable(&query.device);
+    info!("Raw frame capture enabled for {} by {}", query.device, operator);
+    let code = if created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    (code, Json(state.frame_capture.status()))
+}
+
+// DELETE /capture?device=...
+pub async fn disable_capture(
+    State(state): State<Arc<ServerState>>,
+    Extension(Operator(operator)): Extension<Operator>,
+    Query(query): Query<CaptureQuery>,
+) -> (StatusCode, Json<CaptureStatus>) {
+    let removed = state.frame_capture.disable(&query.device);
+    info!("Raw frame capture disabled for {} by {}", query.device, operator);
+    let code = if removed {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    (code, Json(state.frame_capture.status()))
+}
@@ -0,0 +1,46 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::model::modbus::memory_map_models::GEMS_3500;
+use crate::service::{
+    read::diag_read::{DiagGemsQuery, DiagReadQuery, diag_gems_channel, diag_read},
+    server::get_state::ServerState,
+};
+
+// GET /diag/modbus
+pub async fn get_diag_modbus(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<DiagReadQuery>,
+) -> Response {
+    match diag_read(&query, &state.frame_capture).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err((code, message)) => (code, Json(json!({ "error": message }))).into_response(),
+    }
+}
+
+// GET /diag/gems
+pub async fn get_diag_gems(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<DiagGemsQuery>,
+) -> Response {
+    let gems_table = match state.memory_maps.get(GEMS_3500) {
+        Ok(table) => table,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+    match diag_gems_channel(&query, gems_table, &state.frame_capture).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err((code, message)) => (code, Json(json!({ "error": message }))).into_response(),
+    }
+}
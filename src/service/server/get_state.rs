@@ -1,101 +1,284 @@
 use crate::model::{
+    ccm::data_models::CcmMeasurementPoint,
     gas::data_models::GasMeasurementPoint,
-    gems_3005::{
-        data_models::GemsMeasurementPoint, gems_3500_memory_map_models::Gems3500MemoryMapTable,
-    },
+    gems_3005::data_models::GemsMeasurementPoint,
     heat::data_models::HeatMeasurementPoint,
-    iaq::data_models::IaqMeasurementPoint,
+    iaq::data_models::{FrameStats, IaqMeasurementPoint, IaqRegisterMapTable},
+    sihas::data_models::{SihasMeasurementPoint, SihasRegisterMapTable},
+};
+use crate::model::modbus::{
+    gateway_map_models::GatewayRegisterMapTable, memory_map_models::MemoryMapRegistry,
+    write_models::WriteAllowlist,
+};
+use crate::model::alarm::alarm_models::{AlarmRuleTable, AlarmWebhook};
+use crate::model::auth::api_key_models::ApiKeyTable;
+use crate::model::quality::quality_models::QualityRuleTable;
+use crate::model::rollup::rollup_models::RollupWindowTable;
+use crate::model::virtual_point::virtual_point_models::VirtualPointTable;
+use crate::service::alarm::alarm_engine::AlarmEngine;
+use crate::service::cache::{
+    acquisition_timing::AcquisitionTiming, counter_deltas::CounterStore, demand_tracker::DemandTracker, latest_values::LatestValueCache, quality_tracker::QualityTracker,
+    rollups::RollupEngine,
+};
+use crate::service::capture::frame_capture::FrameCapture;
+use crate::service::collect::collection_plan::CollectionPlans;
+use crate::service::scan::scan_jobs::ScanJobs;
+use crate::service::config::validation::{ValidationMode, validate_points};
+use crate::service::read::{
+    iaq::frame_parser::BodyChecksumMode, sihas::util_funcs::poll_function_code_from_env,
+    udp_handler::UdpHandlerRegistry,
 };
 use anyhow::{Result, anyhow};
 use tokio::try_join;
 
 pub struct ServerState {
-    pub gems_3500_memory_map_table: Gems3500MemoryMapTable,
+    pub memory_maps: MemoryMapRegistry,
+    pub collection_plans: CollectionPlans,
     pub gems_measurement_point: Vec<GemsMeasurementPoint>,
     pub iaq_measurement_point: Vec<IaqMeasurementPoint>,
+    pub iaq_register_map_table: IaqRegisterMapTable,
     pub heat_measurement_point: Vec<HeatMeasurementPoint>,
     pub gas_measurement_point: Vec<GasMeasurementPoint>,
+    pub ccm_measurement_point: Vec<CcmMeasurementPoint>,
+    pub sihas_measurement_point: Vec<SihasMeasurementPoint>,
+    pub sihas_register_map_table: SihasRegisterMapTable,
+    pub udp_frame_stats: FrameStats,
+    pub udp_body_checksum: BodyChecksumMode,
+    pub sihas_poll_function_code: Option<u8>,
+    pub udp_handlers: UdpHandlerRegistry,
+    pub frame_capture: FrameCapture,
+    pub gateway_register_map_table: GatewayRegisterMapTable,
+    pub latest_values: LatestValueCache,
+    pub write_allowlist: WriteAllowlist,
+    pub counter_store: CounterStore,
+    pub demand_tracker: DemandTracker,
+    pub quality_rule_table: QualityRuleTable,
+    pub quality_tracker: QualityTracker,
+    pub alarm_rule_table: AlarmRuleTable,
+    pub alarm_webhooks: Vec<AlarmWebhook>,
+    pub alarm_engine: AlarmEngine,
+    pub rollup_window_table: RollupWindowTable,
+    pub rollup_engine: RollupEngine,
+    pub virtual_point_table: VirtualPointTable,
+    pub acquisition_timing: AcquisitionTiming,
+    pub api_keys: ApiKeyTable,
+    pub scan_jobs: ScanJobs,
 }
 
 // 이 함수에서 서버 초기화할때 초기 state를 제공. LUT(Lookup Table)/캐시 역할을 한다.
 // Inititalize the state here when the server initializes. Many of the fields here will act as caches in the form of lookup tables.
 pub async fn get_state() -> Result<ServerState> {
-    let gems_3500_memory_map_table = tokio::spawn(async { Gems3500MemoryMapTable::from_csv() });
+    let mode = ValidationMode::from_env()?;
+    let acquisition_timing = AcquisitionTiming::from_env()?;
+    let udp_body_checksum = BodyChecksumMode::from_env()?;
+    let sihas_poll_function_code = poll_function_code_from_env()?;
+
+    let memory_maps = tokio::spawn(async { MemoryMapRegistry::from_csv() });
+
+    let iaq_register_map_table = tokio::spawn(async { IaqRegisterMapTable::from_csv() });
+
+    let sihas_register_map_table = tokio::spawn(async { SihasRegisterMapTable::from_csv() });
+
+    let gateway_register_map_table = tokio::spawn(async { GatewayRegisterMapTable::from_csv() });
+
+    let write_allowlist = tokio::spawn(async { WriteAllowlist::from_csv() });
 
-    let gems_measurement_point = tokio::spawn(async { GemsMeasurementPoint::from_csv() });
+    let counter_store = tokio::spawn(async { CounterStore::load() });
 
-    let iaq_measurement_point = tokio::spawn(async { IaqMeasurementPoint::from_csv() });
+    let quality_rule_table = tokio::spawn(async { QualityRuleTable::from_csv() });
 
-    let heat_measurement_point = tokio::spawn(async { HeatMeasurementPoint::from_csv() });
+    let alarm_rule_table = tokio::spawn(async { AlarmRuleTable::from_csv() });
 
-    let gas_measurement_point = tokio::spawn(async { GasMeasurementPoint::from_csv() });
+    let alarm_webhooks = tokio::spawn(async { AlarmWebhook::from_csv() });
+
+    let rollup_window_table = tokio::spawn(async { RollupWindowTable::from_csv() });
+
+    let virtual_point_table = tokio::spawn(async { VirtualPointTable::from_csv() });
+
+    let api_keys = tokio::spawn(async { ApiKeyTable::from_csv() });
 
     let results = try_join!(
-        gems_3500_memory_map_table,
-        gems_measurement_point,
-        iaq_measurement_point,
-        heat_measurement_point,
-        gas_measurement_point
+        memory_maps,
+        iaq_register_map_table,
+        sihas_register_map_table,
+        gateway_register_map_table,
+        write_allowlist,
+        counter_store,
+        quality_rule_table,
+        alarm_rule_table,
+        alarm_webhooks,
+        rollup_window_table,
+        virtual_point_table,
+        api_keys
     );
 
     match results {
         Ok(res_tup) => {
-            let gems_3500_memory_map_table = match res_tup.0 {
-                Ok(mmap) => mmap,
+            let memory_maps = match res_tup.0 {
+                Ok(maps) => maps,
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Error while constructing MemoryMapRegistry for ServerState: {:?}",
+                        e
+                    ));
+                }
+            };
+
+            let iaq_register_map_table = match res_tup.1 {
+                Ok(table) => table,
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Error while constructing IaqRegisterMapTable for ServerState: {:?}",
+                        e
+                    ));
+                }
+            };
+
+            let sihas_register_map_table = match res_tup.2 {
+                Ok(table) => table,
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Error while constructing SihasRegisterMapTable for ServerState: {:?}",
+                        e
+                    ));
+                }
+            };
+
+            let gateway_register_map_table = match res_tup.3 {
+                Ok(table) => table,
                 Err(e) => {
                     return Err(anyhow!(
-                        "Error while constructing Gems3500MemoryMap for ServerState: {:?}",
+                        "Error while constructing GatewayRegisterMapTable for ServerState: {:?}",
                         e
                     ));
                 }
             };
 
-            let gems_measurement_point = match res_tup.1 {
-                Ok(bdt) => bdt,
+            let write_allowlist = match res_tup.4 {
+                Ok(allowlist) => allowlist,
                 Err(e) => {
                     return Err(anyhow!(
-                        "Error while constructing GemsMeasurementPoint for ServerState: {:?}",
+                        "Error while constructing WriteAllowlist for ServerState: {:?}",
                         e
                     ));
                 }
             };
 
-            let iaq_measurement_point = match res_tup.2 {
-                Ok(iaqs) => iaqs,
+            let counter_store = match res_tup.5 {
+                Ok(store) => store,
                 Err(e) => {
                     return Err(anyhow!(
-                        "Error while constructing IaqMeasurementPoint for ServerState: {:?}",
+                        "Error while constructing CounterStore for ServerState: {:?}",
                         e
                     ));
                 }
             };
 
-            let heat_measurement_point = match res_tup.3 {
-                Ok(heats) => heats,
+            let quality_rule_table = match res_tup.6 {
+                Ok(table) => table,
                 Err(e) => {
                     return Err(anyhow!(
-                        "Error while constructing HeatMeasurementPoint for ServerState: {:?}",
+                        "Error while constructing QualityRuleTable for ServerState: {:?}",
                         e
                     ));
                 }
             };
 
-            let gas_measurement_point = match res_tup.4 {
-                Ok(gas) => gas,
+            let alarm_rule_table = match res_tup.7 {
+                Ok(table) => table,
                 Err(e) => {
                     return Err(anyhow!(
-                        "Error while constructing GasMeasurementPoint for ServerState: {:?}",
+                        "Error while constructing AlarmRuleTable for ServerState: {:?}",
                         e
                     ));
                 }
             };
 
+            let alarm_webhooks = match res_tup.8 {
+                Ok(hooks) => hooks,
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Error while constructing AlarmWebhook for ServerState: {:?}",
+                        e
+                    ));
+                }
+            };
+
+            let rollup_window_table = match res_tup.9 {
+                Ok(table) => table,
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Error while constructing RollupWindowTable for ServerState: {:?}",
+                        e
+                    ));
+                }
+            };
+
+            let virtual_point_table = match res_tup.10 {
+                Ok(table) => table,
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Error while constructing VirtualPointTable for ServerState: {:?}",
+                        e
+                    ));
+                }
+            };
+
+            let api_keys = match res_tup.11 {
+                Ok(table) => table,
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Error while constructing ApiKeyTable for ServerState: {:?}",
+                        e
+                    ));
+                }
+            };
+
+            // 측정 포인트는 메모리 맵 / IAQ 레지스터 맵과 교차 검증한 뒤 사용
+            let points = validate_points(&memory_maps, &iaq_register_map_table)
+                .and_then(|points| points.enforce(mode))
+                .map_err(|e| anyhow!("Error while validating measurement points: {:?}", e))?;
+
+            virtual_point_table.check_references(&points.measurement_point_ids())?;
+
+            // 포인트별 레지스터/형식은 여기서 한 번만 풀어 둔다 (주기마다는 읽기와 해석만)
+            let collection_plans = CollectionPlans::compile(&memory_maps, &points)
+                .map_err(|e| anyhow!("Error while compiling collection plans: {:?}", e))?;
+
+            let udp_handlers = UdpHandlerRegistry::with_defaults(&sihas_register_map_table);
+
             Ok(ServerState {
-                gems_3500_memory_map_table,
-                gems_measurement_point,
-                iaq_measurement_point,
-                heat_measurement_point,
-                gas_measurement_point,
+                memory_maps,
+                collection_plans,
+                gems_measurement_point: points.gems,
+                iaq_measurement_point: points.iaq,
+                iaq_register_map_table,
+                heat_measurement_point: points.heat,
+                gas_measurement_point: points.gas,
+                ccm_measurement_point: points.ccm,
+                sihas_measurement_point: points.sihas,
+                sihas_register_map_table,
+                udp_frame_stats: FrameStats::default(),
+                udp_body_checksum,
+                sihas_poll_function_code,
+                udp_handlers,
+                frame_capture: FrameCapture::default(),
+                gateway_register_map_table,
+                latest_values: LatestValueCache::default(),
+                write_allowlist,
+                counter_store,
+                demand_tracker: DemandTracker::default(),
+                quality_rule_table,
+                quality_tracker: QualityTracker::default(),
+                alarm_rule_table,
+                alarm_webhooks,
+                alarm_engine: AlarmEngine::default(),
+                rollup_window_table,
+                rollup_engine: RollupEngine::default(),
+                virtual_point_table,
+                acquisition_timing,
+                api_keys,
+                scan_jobs: ScanJobs::default(),
             })
         }
         Err(e) => Err(anyhow!("JoinError while constructing ServerState: {:?}", e)),
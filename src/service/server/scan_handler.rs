@@ -0,0 +1,68 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::service::{
+    scan::{
+        modbus_scanner::{ScanConfig, run_scan},
+        scan_jobs::ScanJob,
+    },
+    server::{auth::Operator, get_state::ServerState},
+};
+use tracing::info;
+
+// POST /scan  (본문: ScanConfig). 백그라운드 작업으로 돌리고 202 + 작업 id 를 바로 돌려준다
+pub async fn post_scan(
+    State(state): State<Arc<ServerState>>,
+    Extension(Operator(operator)): Extension<Operator>,
+    Json(config): Json<ScanConfig>,
+) -> Response {
+    let endpoints_total = match config.validate() {
+        Ok(n) => n,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    let Some(permit) = state.scan_jobs.try_reserve() else {
+        return error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many scans are already running".to_owned(),
+        );
+    };
+
+    let (job, progress) = state.scan_jobs.start(&operator, endpoints_total);
+    info!(
+        "Scan {} of {} ports {} requested by {}",
+        job.id, config.hosts, config.ports, operator
+    );
+
+    let id = job.id;
+    let state = Arc::clone(&state);
+    tokio::spawn(async move {
+        let result = run_scan(&config, &state.memory_maps, &state.frame_capture, &progress).await;
+        state.scan_jobs.finish(id, result);
+        drop(permit);
+    });
+
+    (StatusCode::ACCEPTED, Json(job)).into_response()
+}
+
+// GET /scan/{id}  (진행 상황. 끝나면 발견한 유닛 + gems.csv / heat.csv 초안)
+pub async fn get_scan(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ScanJob>, StatusCode> {
+    state
+        .scan_jobs
+        .get(id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+fn error_response(code: StatusCode, message: String) -> Response {
+    (code, Json(json!({ "error": message }))).into_response()
+}
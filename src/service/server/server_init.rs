@@ -1,18 +1,27 @@
+use crate::service::server::alarm_handler::{acknowledge_alarm, get_alarms};
+use crate::service::server::auth::require_api_key;
+use crate::service::server::capture_handler::{disable_capture, enable_capture, get_capture};
+use crate::service::server::diag_handler::{get_diag_gems, get_diag_modbus};
 use crate::service::server::health_check::health_check;
+use crate::service::server::scan_handler::{get_scan, post_scan};
+use crate::service::server::status_handler::get_status;
+use crate::service::server::write_handler::post_device_write;
 use crate::service::{
     server::get_state::{ServerState, get_state},
     task::task_init::task_init,
+    utils::clock_health::clock_health,
 };
 use anyhow::{Result, anyhow};
 use axum::extract::DefaultBodyLimit;
-use axum::http::{HeaderName, header};
-use axum::routing::get;
+use axum::http::{HeaderName, Method, header};
+use axum::middleware;
+use axum::routing::{get, post, put};
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
 use tower_http::cors::CorsLayer;
-use tracing::info;
+use tracing::{info, warn};
 
 #[inline]
 pub async fn server_initializer() -> Result<String> {
@@ -26,12 +35,48 @@ pub async fn server_initializer() -> Result<String> {
         Err(e) => return Err(anyhow!("Could not create ServerState: {:?}", e)),
     };
 
+    if state.api_keys.rows.is_empty() {
+        warn!("src/files/api_keys.csv is missing or empty; control routes will reject every request");
+    }
+
+    // 시계가 동기화되지 않았으면 recorded_at / acquired_at 을 믿기 어렵다
+    let clock = clock_health();
+    if clock.synchronized == Some(false) {
+        warn!(state = %clock.clock_state, "System clock is not NTP-synchronized; timestamps may drift");
+    }
+
     let healthcheck_router: axum::Router = axum::Router::new()
         .route("/healthcheck", get(health_check))
+        .route("/status", get(get_status))
         // .route("/healthcheck/healthcheck", get(healthcheck_handler)) // simple healthcheck
         .with_state(Arc::clone(&state)); // system diagnosis
 
-    let cors_layer: CorsLayer = CorsLayer::very_permissive().expose_headers([
+    // 조회: 캡처 상태, 알람 목록, 현장 진단 읽기
+    let query_router: axum::Router = axum::Router::new()
+        .route("/capture", get(get_capture))
+        .route("/alarms", get(get_alarms))
+        .route("/diag/modbus", get(get_diag_modbus))
+        .route("/diag/gems", get(get_diag_gems))
+        .with_state(Arc::clone(&state));
+
+    // 상태를 바꾸는 요청: 허용 목록 기반 Modbus 쓰기 (감사 로그 기록), 캡처 on/off, 유닛/채널 스캔 (결과 조회 포함), 알람 확인
+    // x-api-key 필수, CORS 미적용 (브라우저 교차 출처 요청은 사전 요청에서 막힌다)
+    let control_router: axum::Router = axum::Router::new()
+        .route("/devices/{id}/write", post(post_device_write))
+        .route("/capture", put(enable_capture).delete(disable_capture))
+        .route("/scan", post(post_scan))
+        .route("/scan/{id}", get(get_scan))
+        .route("/alarms/{id}/ack", post(acknowledge_alarm))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            require_api_key,
+        ))
+        .with_state(Arc::clone(&state));
+
+    // 교차 출처는 조회(GET)만 허용
+    let cors_layer: CorsLayer = CorsLayer::very_permissive()
+        .allow_methods([Method::GET, Method::HEAD])
+        .expose_headers([
         header::ACCESS_CONTROL_ALLOW_ORIGIN,
         header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
         header::ACCESS_CONTROL_ALLOW_HEADERS,
@@ -62,7 +107,9 @@ pub async fn server_initializer() -> Result<String> {
     // The final router.
     let app: axum::Router = axum::Router::new()
         .merge(healthcheck_router)
+        .merge(query_router)
         .layer(cors_layer)
+        .merge(control_router)
         .layer(DefaultBodyLimit::disable()); // 64MB
 
     // Tokio TCP listener에 IP를 연결해주고 오류처리.
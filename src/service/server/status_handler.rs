@@ -0,0 +1,28 @@
+use axum::{Json, extract::State};
+use serde_derive::Serialize;
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::{
+    model::iaq::data_models::FrameStatsSnapshot,
+    service::{
+        cache::acquisition_timing::SlotTiming,
+        server::get_state::ServerState,
+        utils::clock_health::{ClockHealth, clock_health},
+    },
+};
+
+#[derive(Serialize)]
+pub struct StatusResponse {
+    pub clock: ClockHealth,
+    pub acquisition: BTreeMap<String, SlotTiming>,
+    pub udp_frames: FrameStatsSnapshot,
+}
+
+// GET /status : 시계 동기화 상태, 센서별 마지막 주기 수집 지연, UDP 프레임 통계
+pub async fn get_status(State(state): State<Arc<ServerState>>) -> Json<StatusResponse> {
+    Json(StatusResponse {
+        clock: clock_health(),
+        acquisition: state.acquisition_timing.snapshot(),
+        udp_frames: state.udp_frame_stats.snapshot(),
+    })
+}
@@ -1,22 +1,23 @@
 use crate::{
     model::{
-        gems_3005::data_models::{IAQ, RequestBody},
-        iaq::data_models::{Header, IaqData, Message},
+        gems_3005::data_models::{CCM, IAQ, RequestBody},
+        sihas::data_models::SIHAS,
     },
     service::{
+        alarm::alarm_engine::evaluate_alarms,
         collect::gems_3500_modbus::post_axum_server_direct_data,
-        read::iaq::util_funcs::{
-            ccm_data, format_mac_upper, handle_iaq, read_bytes, read_str_n, read_u8, read_u16,
-            valid_checksum, valid_function_code,
+        read::{
+            iaq::{frame_parser::parse_frame, util_funcs::format_mac_upper},
+            sihas::util_funcs::send_sihas_polls,
+            udp_handler::UdpRecord,
         },
         server::get_state::ServerState,
     },
 };
-use anyhow::{Context, Result};
-use serde_json::to_value;
+use anyhow::Result;
 use uuid::Uuid;
 
-use std::{collections::HashMap, io::Cursor};
+use std::collections::HashMap;
 
 use std::sync::Arc;
 use tokio::{
@@ -27,104 +28,64 @@ use tracing::{error, info, warn};
 
 // UDP 리스너: 5005 포트로 들어오는 패킷 파싱 & 처리
 pub async fn run_udp_listener(state: Arc<ServerState>) -> Result<()> {
-    let mut map: HashMap<Uuid, IaqData> = HashMap::new();
+    // sensor_type → (measurement_point_id → 최신 레코드)
+    let mut map: HashMap<&'static str, HashMap<Uuid, UdpRecord>> = HashMap::new();
+    let function_codes = state.udp_handlers.function_codes();
 
     let socket = UdpSocket::bind("0.0.0.0:5005").await?;
     info!("UDP listener bound to 0.0.0.0:5005");
 
+    // 폴링 방식 장치는 요청을 보내 두고, 응답은 아래 루프에서 보고 프레임과 같이 처리
+    send_sihas_polls(&state, &socket).await;
+
     let listen_future = async {
         let mut buf = [0u8; 1024];
         loop {
             let (len, peer) = socket.recv_from(&mut buf).await?;
             // println!("{}바이트 패킷 수신: {:?} from {}", len, &buf[..len], peer);
-            let data = &buf[..len];
-            let mut cur = Cursor::new(data);
-
-            // 1) 헤더 파싱
-            let hdr = Header {
-                tid: read_u16(&mut cur)?,
-                src: read_u8(&mut cur)?,
-                dst: read_u8(&mut cur)?,
-                data_length: read_u16(&mut cur)?,
-                checksum: read_u8(&mut cur)?,
+            state.frame_capture.capture_udp(peer, &buf[..len]);
+            let frame = match parse_frame(&buf[..len], &function_codes, state.udp_body_checksum) {
+                Ok(frame) => {
+                    state.udp_frame_stats.record_accepted();
+                    frame
+                }
+                Err(e) => {
+                    // 잘린/손상된 패킷은 태스크를 죽이지 않고 버린다
+                    state.udp_frame_stats.record_error(&e);
+                    error!(error = %e, "Dropping malformed UDP frame from {}", peer);
+                    continue;
+                }
             };
-            if !valid_checksum(&hdr) {
-                error!(?hdr, "Invalid header checksum from {}", peer);
-                continue;
-            }
 
-            // 2) 펑션코드
-            let func = read_u8(&mut cur)?;
-            if !valid_function_code(func) {
-                error!(code = func, "Invalid function code from {}", peer);
+            // 2) 타입별 분기 처리
+            let Some(handler) = state
+                .udp_handlers
+                .get(frame.device_type, frame.function_code)
+            else {
+                warn!(
+                    dtype = frame.device_type,
+                    code = frame.function_code,
+                    "Unknown device type from {}",
+                    peer
+                );
                 continue;
-            }
-
-            // 3) 메타 정보 -> device_type, mac_str만 필요하나 바이트 소모는 필요해서 나머지 변수들 남겨놓음
-            let _local_addr = {
-                let b = read_bytes(&mut cur, 6)?;
-                let mut arr = [0u8; 6];
-                arr.copy_from_slice(&b);
-                arr
             };
-            let _ssid = read_str_n(&mut cur, 32)?;
 
-            let mac_bytes = {
-                let b = read_bytes(&mut cur, 6)?;
-                let mut arr = [0u8; 6];
-                arr.copy_from_slice(&b);
-                arr
-            };
-
-            let device_type = read_u8(&mut cur)?;
-            let _cfg = read_u8(&mut cur)?;
-
-            let mac_str = format_mac_upper(&mac_bytes);
-
-            // 4) 메시지
-            let version = read_u16(&mut cur)?;
-            let number_of_reg = read_u8(&mut cur)?; // 한 번만 읽기!
-            let offset = read_u16(&mut cur)?;
-
-            let registers: Vec<u16> = (0..number_of_reg)
-                .map(|_| read_u16(&mut cur).unwrap())
-                .collect();
-
-            let checksum = read_u16(&mut cur)?;
-
-            let msg = Message {
-                version,
-                count: number_of_reg,
-                offset,
-                registers,
-                checksum,
-            };
-
-            // 5) 타입별 분기 처리
-            match device_type {
-                12 => {
-                    // IAQ 센서
-                    match handle_iaq(state.clone(), mac_str, msg.registers).await {
-                        Ok(partial_map) => {
-                            for (mp_id, iaqdata) in partial_map {
-                                map.entry(mp_id)
-                                    .and_modify(|existing| *existing = iaqdata.clone())
-                                    .or_insert(iaqdata);
-                            }
-                        }
-                        Err(e) => {
-                            error!(error = ?e, "Error handling IAQ packet");
-                        }
-                    }
-                }
-                5 => {
-                    // 스마트콘센트
-                    if let Err(e) = ccm_data(&msg.registers) {
-                        error!(error = ?e, "Error handling CCM packet");
+            match handler.handle(&state, &frame) {
+                Ok(records) => {
+                    let entry = map.entry(handler.sensor_type()).or_default();
+                    for record in records {
+                        state.latest_values.update_from_value(&record.data);
+                        entry.insert(record.measurement_point_id, record);
                     }
                 }
-                other => {
-                    warn!(dtype = other, "Unknown device type from {}", peer);
+                Err(e) => {
+                    error!(
+                        error = ?e,
+                        mac = %format_mac_upper(&frame.mac),
+                        "Error handling {} packet",
+                        handler.sensor_type()
+                    );
                 }
             }
         }
@@ -132,28 +93,64 @@ pub async fn run_udp_listener(state: Arc<ServerState>) -> Result<()> {
     match timeout(Duration::from_secs(30), listen_future).await {
         Ok(inner_res) => inner_res, // 30초 안에 에러가 나면 그 에러를 그대로 리턴
         Err(_) => {
-            info!("UDP listener timed out after 30s");
-
-            if map.is_empty() {
-                info!("No matching data types → skipping API call");
-                return Ok(());
+            info!(
+                stats = ?state.udp_frame_stats.snapshot(),
+                "UDP listener timed out after 30s"
+            );
+
+            // 수신이 없던 타입도 알람(오프라인) 평가는 돌린다
+            for sensor_type in [IAQ, CCM, SIHAS] {
+                let records = map
+                    .remove(sensor_type)
+                    .map(|m| m.into_values().collect())
+                    .unwrap_or_default();
+                post_records(&state, sensor_type, records).await?;
             }
-
-            let records: Vec<IaqData> = map.into_iter().map(|(_, iaqdata)| iaqdata).collect();
-
-            // 4) HTTP POST
-            let params = RequestBody {
-                sensor_type: IAQ.to_owned(),
-                building_id: records[0].building_id,
-                data: to_value(&records).context("Failed to convert records to JSON Value")?,
-            };
-
-            if let Err(e) = post_axum_server_direct_data(params).await {
-                error!("Error posting IAQ data to Axum server: {:?}", e);
-            } else {
-                info!("Successfully posted IAQ data");
+            for (sensor_type, records) in map {
+                post_records(&state, sensor_type, records.into_values().collect()).await?;
             }
             Ok(())
         }
     }
 }
+
+// 센서 타입별 수집 결과를 건물마다 나눠 전송. 비어 있으면 호출하지 않는다.
+async fn post_records(
+    state: &ServerState,
+    sensor_type: &str,
+    records: Vec<UdpRecord>,
+) -> Result<()> {
+    let mut bodies = RequestBody::per_building(sensor_type, records, |r| r.building_id)?;
+    for params in &mut bodies {
+        state
+            .quality_tracker
+            .annotate_body(&state.quality_rule_table, params);
+    }
+    evaluate_alarms(state, sensor_type, &bodies);
+    let rollups = state
+        .rollup_engine
+        .observe(&state.rollup_window_table, sensor_type, &bodies);
+    bodies.extend(rollups);
+
+    if bodies.is_empty() {
+        info!("No matching {} data → skipping API call", sensor_type);
+        return Ok(());
+    }
+
+    for params in bodies {
+        // HTTP POST
+        let building_id = params.building_id;
+        if let Err(e) = post_axum_server_direct_data(params).await {
+            error!(
+                "Error posting {} data for building {} to Axum server: {:?}",
+                sensor_type, building_id, e
+            );
+        } else {
+            info!(
+                "Successfully posted {} data for building {}",
+                sensor_type, building_id
+            );
+        }
+    }
+    Ok(())
+}
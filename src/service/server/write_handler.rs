@@ -0,0 +1,31 @@
+use axum::{
+    Json,
+    Extension,
+    extract::{ConnectInfo, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::{net::SocketAddr, sync::Arc};
+
+use crate::{
+    model::modbus::write_models::WriteRequest,
+    service::{
+        control::modbus_write::write_device,
+        server::{auth::Operator, get_state::ServerState},
+    },
+};
+
+// POST /devices/{id}/write  (id = measurement_point_id)
+pub async fn post_device_write(
+    State(state): State<Arc<ServerState>>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    Extension(Operator(operator)): Extension<Operator>,
+    Path(id): Path<String>,
+    Json(req): Json<WriteRequest>,
+) -> Response {
+    match write_device(&state, &id, req, &operator, remote_addr).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err((code, message)) => (code, Json(json!({ "error": message }))).into_response(),
+    }
+}
@@ -0,0 +1,509 @@
+use anyhow::{Result, anyhow};
+use serde_derive::Deserialize;
+use std::{
+    collections::HashMap,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    net::TcpListener,
+    time::{Duration, interval, sleep},
+};
+use tokio_modbus::{
+    ExceptionCode, Request, Response, SlaveRequest,
+    server::{
+        Service,
+        tcp::{Server, accept_tcp_connection},
+    },
+};
+use tracing::{info, warn};
+
+use crate::{
+    model::modbus::memory_map_models::{GEMS_3500, MemoryMapRegistry, MemoryMapTable},
+    service::utils::xorshift::XorShift64,
+};
+
+// 시뮬레이터가 흉내 낼 장치 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulatorProfile {
+    Gems,
+    Heat,
+    Gas,
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    pub profile: SimulatorProfile,
+    pub bind: SocketAddr,
+    pub latency: Duration,
+    pub drop_rate: f64,
+    pub exception_rate: f64,
+    pub script: Option<String>,
+    pub seed: Option<u64>,
+}
+
+impl SimulatorConfig {
+    // simulate --profile gems|heat|gas [--bind 127.0.0.1:15020] [--latency-ms 0]
+    //          [--drop-rate 0.0] [--exception-rate 0.0] [--script file.csv] [--seed N]
+    pub fn from_args(args: &[String]) -> Result<SimulatorConfig> {
+        let mut config = SimulatorConfig {
+            profile: SimulatorProfile::Gems,
+            bind: "127.0.0.1:15020".parse()?,
+            latency: Duration::ZERO,
+            drop_rate: 0.0,
+            exception_rate: 0.0,
+            script: None,
+            seed: None,
+        };
+
+        let mut it = args.iter();
+        while let Some(flag) = it.next() {
+            let mut value = || {
+                it.next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", flag))
+            };
+            match flag.as_str() {
+                "--profile" => {
+                    config.profile = match value()?.as_str() {
+                        "gems" => SimulatorProfile::Gems,
+                        "heat" => SimulatorProfile::Heat,
+                        "gas" => SimulatorProfile::Gas,
+                        other => return Err(anyhow!("Unknown simulator profile: {}", other)),
+                    }
+                }
+                "--bind" => config.bind = value()?.parse()?,
+                "--latency-ms" => config.latency = Duration::from_millis(value()?.parse()?),
+                "--drop-rate" => config.drop_rate = value()?.parse()?,
+                "--exception-rate" => config.exception_rate = value()?.parse()?,
+                "--script" => config.script = Some(value()?.clone()),
+                "--seed" => config.seed = Some(value()?.parse()?),
+                other => return Err(anyhow!("Unknown simulator option: {}", other)),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+// 스크립트 파일 행: 지정한 레지스터 값을 매 갱신 후 고정
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScriptRow {
+    pub table: String, // input | holding
+    pub address: u16,
+    pub value: u16,
+}
+
+#[derive(Default)]
+struct RegisterBank {
+    input: HashMap<u16, u16>,
+    holding: HashMap<u16, u16>,
+}
+
+struct Simulator {
+    config: SimulatorConfig,
+    bank: Mutex<RegisterBank>,
+    rng: Mutex<XorShift64>,
+    // 적산값 (주소 → 현재값)
+    counters: Mutex<HashMap<u16, f64>>,
+    memory_map: Option<MemoryMapTable>,
+    script: Vec<ScriptRow>,
+}
+
+impl Simulator {
+    fn refresh(&self) {
+        let mut rng = self.rng.lock().expect("simulator rng poisoned");
+        let mut counters = self.counters.lock().expect("simulator counters poisoned");
+        let mut bank = self.bank.lock().expect("simulator bank poisoned");
+
+        match self.config.profile {
+            SimulatorProfile::Gems => {
+                if let Some(table) = &self.memory_map {
+                    refresh_gems(table, &mut bank, &mut rng, &mut counters);
+                }
+            }
+            SimulatorProfile::Heat => refresh_heat(&mut bank, &mut rng, &mut counters),
+            SimulatorProfile::Gas => refresh_gas(&mut bank, &mut rng, &mut counters),
+        }
+
+        for row in &self.script {
+            let target = if row.table == "holding" {
+                &mut bank.holding
+            } else {
+                &mut bank.input
+            };
+            target.insert(row.address, row.value);
+        }
+    }
+
+    async fn respond(&self, req: SlaveRequest<'static>) -> Result<Option<Response>, ExceptionCode> {
+        if !self.config.latency.is_zero() {
+            sleep(self.config.latency).await;
+        }
+
+        let fail = {
+            let mut rng = self.rng.lock().expect("simulator rng poisoned");
+            rng.chance(self.config.exception_rate)
+        };
+        if fail {
+            return Err(ExceptionCode::ServerDeviceFailure);
+        }
+
+        let bank = self.bank.lock().expect("simulator bank poisoned");
+        match req.request {
+            Request::ReadInputRegisters(addr, cnt) => {
+                read_words(&bank.input, addr, cnt).map(|w| Some(Response::ReadInputRegisters(w)))
+            }
+            Request::ReadHoldingRegisters(addr, cnt) => read_words(&bank.holding, addr, cnt)
+                .map(|w| Some(Response::ReadHoldingRegisters(w))),
+            _ => Err(ExceptionCode::IllegalFunction),
+        }
+    }
+}
+
+fn read_words(table: &HashMap<u16, u16>, addr: u16, cnt: u16) -> Result<Vec<u16>, ExceptionCode> {
+    (0..cnt)
+        .map(|i| {
+            addr.checked_add(i)
+                .and_then(|a| table.get(&a).copied())
+                .ok_or(ExceptionCode::IllegalDataAddress)
+        })
+        .collect()
+}
+
+// 공학값 → 레지스터 워드 (상위 워드 먼저)
+fn write_scaled(table: &mut HashMap<u16, u16>, addr: u16, data_type: &str, value: f64, divide_by: f64) {
+    let raw = (value * divide_by).round();
+    match data_type {
+        "UINT16" => {
+            table.insert(addr, raw.clamp(0.0, u16::MAX as f64) as u16);
+        }
+        "INT16" => {
+            table.insert(addr, raw.clamp(i16::MIN as f64, i16::MAX as f64) as i16 as u16);
+        }
+        "UINT32" | "INT32" => {
+            let v = if data_type == "UINT32" {
+                raw.clamp(0.0, u32::MAX as f64) as u32
+            } else {
+                raw.clamp(i32::MIN as f64, i32::MAX as f64) as i32 as u32
+            };
+            insert_pair(table, addr, (v >> 16) as u16, v as u16);
+        }
+        _ => {
+            table.insert(addr, 0);
+        }
+    }
+}
+
+// 두 워드 값. 다음 주소가 없으면(0xFFFF) 넣지 않는다
+fn insert_pair(table: &mut HashMap<u16, u16>, addr: u16, first: u16, second: u16) {
+    let Some(next) = addr.checked_add(1) else {
+        warn!("Simulator: two-word value at {:#06x} does not fit", addr);
+        return;
+    };
+    table.insert(addr, first);
+    table.insert(next, second);
+}
+
+fn bump_counter(counters: &mut HashMap<u16, f64>, addr: u16, rng: &mut XorShift64, step: f64) -> f64 {
+    let c = counters.entry(addr).or_insert_with(|| rng.range_f64(1_000.0, 50_000.0));
+    *c += rng.range_f64(0.0, step);
+    *c
+}
+
+// GEMS-3500: 메모리맵 전체를 채우고 채널 블록(register_from_ch)의 의미에 맞는 범위로 값 생성
+fn refresh_gems(
+    table: &MemoryMapTable,
+    bank: &mut RegisterBank,
+    rng: &mut XorShift64,
+    counters: &mut HashMap<u16, f64>,
+) {
+    for row in &table.rows {
+        let Some(data_type) = row.data_type.as_deref() else {
+            bank.input.insert(row.memory_address, 0);
+            continue;
+        };
+        let addr = row.memory_address;
+        let divide_by = row.divide_by.unwrap_or(1) as f64;
+        let is_counter = row
+            .phase
+            .as_deref()
+            .is_some_and(|p| p.eq_ignore_ascii_case("kwh"));
+
+        let value = if is_counter {
+            bump_counter(counters, addr, rng, 0.5)
+        } else if (2420..8000).contains(&addr) {
+            match (addr - 2420) % 64 {
+                0 => 3.0, // wire
+                16 | 32 | 48 => rng.range_f64(215.0, 230.0),
+                2 | 18 | 34 | 50 => rng.range_f64(0.0, 50.0),
+                4 | 20 | 36 | 52 => rng.range_f64(0.0, 10_000.0),
+                10 | 29 | 45 | 61 => rng.range_f64(0.8, 1.0),
+                _ => rng.range_f64(0.0, 100.0),
+            }
+        } else {
+            rng.range_f64(0.0, 100.0)
+        };
+
+        write_scaled(&mut bank.input, addr, data_type, value, divide_by);
+    }
+}
+
+// 열량계: holding register, float32 (하위 워드 먼저)
+fn refresh_heat(bank: &mut RegisterBank, rng: &mut XorShift64, counters: &mut HashMap<u16, f64>) {
+    let values = [
+        (0x00, rng.range_f64(5.0, 15.0)),                  // 순시유량
+        (0x02, rng.range_f64(20.0, 80.0)),                 // 순시열량
+        (0x20, rng.range_f64(40.0, 50.0)),                 // 공급온도
+        (0x22, rng.range_f64(30.0, 40.0)),                 // 환수온도
+        (0x70, bump_counter(counters, 0x70, rng, 0.2)),   // 적산유량
+        (0x76, bump_counter(counters, 0x76, rng, 1.0)),   // 적산열량
+    ];
+
+    for (addr, value) in values {
+        let bits = (value as f32).to_bits();
+        insert_pair(&mut bank.holding, addr, bits as u16, (bits >> 16) as u16);
+    }
+}
+
+// 가스미터: input register, 정수부/소수부 분리 인코딩 (collect::gas 참고)
+fn refresh_gas(bank: &mut RegisterBank, rng: &mut XorShift64, counters: &mut HashMap<u16, f64>) {
+    let cumulative = bump_counter(counters, 0x00, rng, 0.5) as u32;
+    bank.input.insert(0x00, (cumulative / 10_000) as u16);
+    bank.input.insert(0x01, (cumulative % 10_000) as u16);
+
+    for (addr, value) in [(0x02, rng.range_f64(0.0, 30.0)), (0x08, rng.range_f64(1.0, 5.0))] {
+        insert_pair(
+            &mut bank.input,
+            addr,
+            (value / 100.0) as u16,
+            ((value % 100.0) * 100.0) as u16,
+        );
+    }
+    for addr in 0x04..0x08 {
+        bank.input.insert(addr, 0);
+    }
+
+    let temp = rng.range_f64(-5.0, 25.0);
+    bank.input.insert(0x0A, if temp < 0.0 { 1 } else { 0 });
+    bank.input.insert(0x0B, (temp.abs() * 100.0) as u16);
+}
+
+#[derive(Clone)]
+struct SimulatorService {
+    sim: Arc<Simulator>,
+}
+
+impl Service for SimulatorService {
+    type Request = SlaveRequest<'static>;
+    type Response = Option<Response>;
+    type Exception = ExceptionCode;
+    type Future = Pin<Box<dyn Future<Output = Result<Option<Response>, ExceptionCode>> + Send>>;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let sim = Arc::clone(&self.sim);
+        Box::pin(async move { sim.respond(req).await })
+    }
+}
+
+fn load_script(path: &str) -> Result<Vec<ScriptRow>> {
+    let mut rdr = csv::Reader::from_path(path)?;
+    let mut rows = Vec::new();
+    for result in rdr.deserialize() {
+        let row: ScriptRow = result?;
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+// 로컬 Modbus TCP 장치 시뮬레이터. 종료될 때까지 serve 한다.
+pub async fn run_simulator(config: SimulatorConfig) -> Result<()> {
+    let listener = TcpListener::bind(config.bind)
+        .await
+        .map_err(|e| anyhow!("Could not bind simulator to {}: {:?}", config.bind, e))?;
+    serve_simulator(config, listener).await
+}
+
+// 이미 바인딩된 리스너로 시뮬레이터 실행 (테스트는 127.0.0.1:0 으로 빈 포트를 받는다)
+pub async fn serve_simulator(config: SimulatorConfig, listener: TcpListener) -> Result<()> {
+    let memory_map = match config.profile {
+        SimulatorProfile::Gems => Some(MemoryMapRegistry::from_csv()?.get(GEMS_3500)?.clone()),
+        _ => None,
+    };
+    let script = match &config.script {
+        Some(path) => load_script(path)?,
+        None => Vec::new(),
+    };
+    let rng = match config.seed {
+        Some(seed) => XorShift64::new(seed),
+        None => XorShift64::from_time(),
+    };
+
+    let sim = Arc::new(Simulator {
+        config: config.clone(),
+        bank: Mutex::new(RegisterBank::default()),
+        rng: Mutex::new(rng),
+        counters: Mutex::new(HashMap::new()),
+        memory_map,
+        script,
+    });
+    sim.refresh();
+
+    {
+        let sim = Arc::clone(&sim);
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(1));
+            loop {
+                ticker.tick().await;
+                sim.refresh();
+            }
+        });
+    }
+
+    info!(
+        "Modbus simulator ({:?}) listening on {}",
+        config.profile,
+        listener.local_addr()?
+    );
+
+    let server = Server::new(listener);
+    let on_connected = |stream, socket_addr| {
+        let sim = Arc::clone(&sim);
+        async move {
+            let drop = {
+                let mut rng = sim.rng.lock().expect("simulator rng poisoned");
+                rng.chance(sim.config.drop_rate)
+            };
+            if drop {
+                warn!("Simulator dropping connection from {}", socket_addr);
+                return Ok(None);
+            }
+            accept_tcp_connection(stream, socket_addr, |_| {
+                Ok(Some(SimulatorService {
+                    sim: Arc::clone(&sim),
+                }))
+            })
+        }
+    };
+    let on_process_error = |e| warn!("Simulator connection error: {:?}", e);
+
+    server
+        .serve(&on_connected, on_process_error)
+        .await
+        .map_err(|e| anyhow!("Simulator server failed: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        model::{
+            gas::data_models::GasMeasurementPoint, gems_3005::data_models::GemsMeasurementPoint,
+            heat::data_models::HeatMeasurementPoint,
+        },
+        service::{
+            capture::frame_capture::FrameCapture,
+            collect::{
+                collection_plan::CollectionPlans, gas::read_gas_point, heat::read_heat_point,
+            },
+            config::validation::ValidatedPoints,
+            read::read_from_addr::read_from_point_map,
+        },
+    };
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    // 수집 경로 전체(계획 → 읽기 → 해석)를 장치 없이 시뮬레이터로 확인한다
+    async fn start(profile: SimulatorProfile) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let config = SimulatorConfig {
+            profile,
+            bind: addr,
+            latency: Duration::ZERO,
+            drop_rate: 0.0,
+            exception_rate: 0.0,
+            script: None,
+            seed: Some(7),
+        };
+        tokio::spawn(serve_simulator(config, listener));
+        addr
+    }
+
+    #[tokio::test]
+    async fn collectors_read_simulated_devices() {
+        let gems = start(SimulatorProfile::Gems).await;
+        let heat = start(SimulatorProfile::Heat).await;
+        let gas = start(SimulatorProfile::Gas).await;
+
+        let building_id = Uuid::from_u128(0xA);
+        let points = ValidatedPoints {
+            gems: vec![GemsMeasurementPoint {
+                building_id,
+                measurement_point_id: Uuid::from_u128(1),
+                host: gems.ip(),
+                port: gems.port() as i32,
+                unit_id: 1,
+                channel: 1,
+                export_sum_status: false,
+            }],
+            heat: vec![HeatMeasurementPoint {
+                building_id,
+                measurement_point_id: Uuid::from_u128(2),
+                host: heat.ip(),
+                port: heat.port() as i32,
+                unit_id: 1,
+            }],
+            gas: vec![GasMeasurementPoint {
+                building_id,
+                measurement_point_id: Uuid::from_u128(3),
+                host: gas.ip(),
+                port: gas.port() as i32,
+                unit_id: 1,
+            }],
+            iaq: Vec::new(),
+            ccm: Vec::new(),
+            sihas: Vec::new(),
+            problems: Vec::new(),
+        };
+        let plans =
+            CollectionPlans::compile(&MemoryMapRegistry::from_csv().unwrap(), &points).unwrap();
+        let capture = FrameCapture::default();
+        let slot = Utc::now();
+
+        let sets = read_from_point_map(&capture, &plans.gems[0], slot)
+            .await
+            .unwrap();
+        assert_eq!(sets.len(), 1);
+        assert!(sets[0].r_v.is_some_and(|v| (215.0..=230.0).contains(&v)));
+        assert_eq!(sets[0].recorded_at, slot);
+
+        let h = read_heat_point(&points.heat[0], &plans.heat_registers, &capture, slot)
+            .await
+            .unwrap();
+        assert!(h.instant_heat.is_some_and(|v| (20.0..=80.0).contains(&v)));
+        assert!(
+            h.supply_temperature
+                .is_some_and(|v| (40.0..=50.0).contains(&v))
+        );
+        assert!(h.cumulative_heat.is_some());
+
+        let g = read_gas_point(&points.gas[0], &plans.gas_registers, &capture, slot)
+            .await
+            .unwrap();
+        assert!(g.cumulative_flow.is_some());
+        assert!(g.temp.is_some_and(|v| (-5.0..=25.0).contains(&v)));
+    }
+
+    #[test]
+    fn two_word_values_stop_at_the_last_address() {
+        let mut table = HashMap::new();
+        write_scaled(&mut table, 0xFFFF, "UINT32", 70_000.0, 1.0);
+        assert!(table.is_empty());
+
+        write_scaled(&mut table, 0xFFFE, "UINT32", 70_000.0, 1.0);
+        assert_eq!(table.get(&0xFFFE), Some(&1));
+        assert_eq!(table.get(&0xFFFF), Some(&4_464));
+    }
+}
@@ -0,0 +1,310 @@
+use anyhow::{Result, anyhow};
+use std::{collections::BTreeSet, net::SocketAddr};
+use tokio::{
+    net::UdpSocket,
+    time::{Duration, sleep},
+};
+use tracing::{info, warn};
+
+use crate::{
+    model::{
+        ccm::data_models::CcmMeasurementPoint,
+        iaq::data_models::{Frame, Header, IaqMeasurementPoint, IaqRegisterMapTable, Message},
+    },
+    service::{
+        read::{
+            ccm::handler::CCM_DEVICE_TYPE,
+            iaq::{
+                frame_parser::{HEADER_LEN, encode_frame},
+                handler::IAQ_DEVICE_TYPE,
+                util_funcs::header_checksum,
+            },
+            sihas::util_funcs::parse_mac,
+            udp_handler::REPORT_FUNCTION_CODE,
+        },
+        utils::xorshift::XorShift64,
+    },
+};
+
+// 일부러 손상시킨 프레임 종류 (FrameError 사유와 1:1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corruption {
+    None,
+    Truncated,
+    LengthMismatch,
+    HeaderChecksum,
+    BodyChecksum,
+    FunctionCode,
+    Random,
+}
+
+#[derive(Debug, Clone)]
+pub struct UdpGeneratorConfig {
+    pub target: SocketAddr,
+    pub rounds: u32,
+    pub interval: Duration,
+    pub corrupt: Corruption,
+    pub mac: Option<String>,
+    pub seed: Option<u64>,
+}
+
+impl UdpGeneratorConfig {
+    // udp-send [--target 127.0.0.1:5005] [--rounds 1] [--interval-ms 1000]
+    //          [--corrupt none|truncated|length|header-checksum|body-checksum|function-code|random]
+    //          [--mac AA:BB:CC:DD:EE:FF] [--seed N]
+    pub fn from_args(args: &[String]) -> Result<UdpGeneratorConfig> {
+        let mut config = UdpGeneratorConfig {
+            target: "127.0.0.1:5005".parse()?,
+            rounds: 1,
+            interval: Duration::from_millis(1000),
+            corrupt: Corruption::None,
+            mac: None,
+            seed: None,
+        };
+
+        let mut it = args.iter();
+        while let Some(flag) = it.next() {
+            let mut value = || {
+                it.next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", flag))
+            };
+            match flag.as_str() {
+                "--target" => config.target = value()?.parse()?,
+                "--rounds" => config.rounds = value()?.parse()?,
+                "--interval-ms" => config.interval = Duration::from_millis(value()?.parse()?),
+                "--corrupt" => {
+                    config.corrupt = match value()?.as_str() {
+                        "none" => Corruption::None,
+                        "truncated" => Corruption::Truncated,
+                        "length" => Corruption::LengthMismatch,
+                        "header-checksum" => Corruption::HeaderChecksum,
+                        "body-checksum" => Corruption::BodyChecksum,
+                        "function-code" => Corruption::FunctionCode,
+                        "random" => Corruption::Random,
+                        other => return Err(anyhow!("Unknown corruption: {}", other)),
+                    }
+                }
+                "--mac" => config.mac = Some(value()?.clone()),
+                "--seed" => config.seed = Some(value()?.parse()?),
+                other => return Err(anyhow!("Unknown udp-send option: {}", other)),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+// 보고 프레임 (0x24) 생성
+pub fn report_frame(tid: u16, mac: [u8; 6], device_type: u8, registers: Vec<u16>) -> Vec<u8> {
+    encode_frame(&Frame {
+        header: Header {
+            tid,
+            src: 1,
+            dst: 0,
+            data_length: 0,
+            checksum: 0,
+        },
+        function_code: REPORT_FUNCTION_CODE,
+        local_addr: [10, 1, 0, 41, 0x13, 0x8D],
+        ssid: String::from("sensor-net"),
+        mac,
+        device_type,
+        cfg: 0,
+        message: Message {
+            version: 0,
+            count: registers.len() as u8,
+            offset: 0,
+            registers,
+            checksum: 0,
+        },
+    })
+}
+
+// iaq_type 별 그럴듯한 값 범위
+fn iaq_range(iaq_type: &str) -> (f64, f64) {
+    match iaq_type {
+        "temperature" => (18.0, 28.0),
+        "humidity" => (30.0, 60.0),
+        "co2" => (400.0, 1200.0),
+        "pm25" => (0.0, 35.0),
+        "pm10" => (0.0, 80.0),
+        "tvoc" => (0.0, 500.0),
+        "illuminance" => (0.0, 800.0),
+        _ => (0.0, 0.0),
+    }
+}
+
+const IAQ_REGISTER_COUNT: usize = 64;
+
+// 레지스터 맵을 거꾸로 적용해 64개 IAQ 레지스터 생성
+pub fn iaq_registers(table: &IaqRegisterMapTable, rng: &mut XorShift64) -> Vec<u16> {
+    let mut regs = vec![0u16; IAQ_REGISTER_COUNT];
+    for row in table.layout(0) {
+        let (min, max) = iaq_range(&row.iaq_type);
+        let raw = (rng.range_f64(min, max) * row.divide_by).round();
+        let words = match row.data_type.as_str() {
+            "INT16" => vec![raw as i16 as u16],
+            "UINT16" => vec![raw as u16],
+            "INT32" | "UINT32" => {
+                let v = raw as i64 as u32;
+                vec![(v >> 16) as u16, v as u16]
+            }
+            _ => continue,
+        };
+        // 맵에 64개를 넘는 레지스터가 있으면 그 행만 건너뛴다
+        let end = row.register.saturating_add(words.len());
+        let Some(slot) = regs.get_mut(row.register..end) else {
+            warn!(
+                iaq_type = %row.iaq_type,
+                "Register {} is outside the {} generated registers; skipping",
+                row.register,
+                IAQ_REGISTER_COUNT
+            );
+            continue;
+        };
+        slot.copy_from_slice(&words);
+    }
+    regs
+}
+
+// 스마트콘센트 64개 레지스터 (ccm_data 의 역변환)
+pub fn ccm_registers(rng: &mut XorShift64) -> Vec<u16> {
+    let mut regs = vec![0u16; 64];
+    let month = rng.range_f64(0.0, 200_000.0) as u32;
+    regs[0] = 1;
+    regs[1] = (rng.range_f64(215.0, 230.0) * 100.0) as u16;
+    regs[2] = (rng.range_f64(0.0, 10.0) * 1000.0) as u16;
+    regs[3] = (rng.range_f64(0.0, 2000.0) * 10.0) as u16;
+    regs[4] = (rng.range_f64(0.8, 1.0) * 10.0) as u16;
+    regs[5] = (rng.range_f64(0.0, 20.0) * 10.0) as u16;
+    regs[26] = month as u16;
+    regs[27] = (month >> 16) as u16;
+    regs
+}
+
+// 정상 프레임을 지정한 방식으로 손상
+pub fn corrupt_frame(mut bytes: Vec<u8>, corruption: Corruption, rng: &mut XorShift64) -> Vec<u8> {
+    let corruption = match corruption {
+        Corruption::Random => match rng.next_u64() % 5 {
+            0 => Corruption::Truncated,
+            1 => Corruption::LengthMismatch,
+            2 => Corruption::HeaderChecksum,
+            3 => Corruption::BodyChecksum,
+            _ => Corruption::FunctionCode,
+        },
+        other => other,
+    };
+
+    // 헤더의 data_length 를 바꾼 뒤 헤더 체크섬을 다시 맞춘다
+    let set_length = |bytes: &mut Vec<u8>, len: u16| {
+        bytes[4..6].copy_from_slice(&len.to_be_bytes());
+        let hdr = Header {
+            tid: u16::from_be_bytes([bytes[0], bytes[1]]),
+            src: bytes[2],
+            dst: bytes[3],
+            data_length: len,
+            checksum: 0,
+        };
+        bytes[6] = header_checksum(&hdr);
+    };
+
+    match corruption {
+        Corruption::None | Corruption::Random => {}
+        Corruption::Truncated => {
+            // 레지스터 중간에서 자르되 헤더는 잘린 길이와 일치하게
+            let cut = bytes.len() - 1 - (rng.next_u64() as usize % 60);
+            bytes.truncate(cut);
+            let len = (bytes.len() - HEADER_LEN) as u16;
+            set_length(&mut bytes, len);
+        }
+        Corruption::LengthMismatch => {
+            let len = (bytes.len() - HEADER_LEN) as u16 + 1;
+            set_length(&mut bytes, len);
+        }
+        Corruption::HeaderChecksum => bytes[6] = bytes[6].wrapping_add(1),
+        Corruption::BodyChecksum => {
+            let last = bytes.len() - 1;
+            bytes[last] = bytes[last].wrapping_add(1);
+        }
+        Corruption::FunctionCode => bytes[HEADER_LEN] = 0x99,
+    }
+    bytes
+}
+
+// iaq.csv / ccm.csv 의 장치들을 흉내 내 리스너로 프레임 전송
+pub async fn run_udp_generator(config: UdpGeneratorConfig) -> Result<()> {
+    let table = IaqRegisterMapTable::from_csv()?;
+    let mut devices: BTreeSet<(String, u8)> = BTreeSet::new();
+    for mp in IaqMeasurementPoint::from_csv()? {
+        devices.insert((mp.mac.to_uppercase(), IAQ_DEVICE_TYPE));
+    }
+    for mp in CcmMeasurementPoint::from_csv()? {
+        devices.insert((mp.mac.to_uppercase(), CCM_DEVICE_TYPE));
+    }
+    if let Some(mac) = &config.mac {
+        devices.retain(|(m, _)| m.eq_ignore_ascii_case(mac));
+    }
+    if devices.is_empty() {
+        return Err(anyhow!("No devices to simulate"));
+    }
+
+    let mut rng = match config.seed {
+        Some(seed) => XorShift64::new(seed),
+        None => XorShift64::from_time(),
+    };
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    let mut tid: u16 = 0;
+
+    for round in 0..config.rounds {
+        for (mac, device_type) in &devices {
+            let registers = if *device_type == IAQ_DEVICE_TYPE {
+                iaq_registers(&table, &mut rng)
+            } else {
+                ccm_registers(&mut rng)
+            };
+            let frame = report_frame(tid, parse_mac(mac)?, *device_type, registers);
+            let frame = corrupt_frame(frame, config.corrupt, &mut rng);
+            socket.send_to(&frame, config.target).await?;
+            tid = tid.wrapping_add(1);
+        }
+        info!(
+            "Sent round {} ({} devices) to {}",
+            round + 1,
+            devices.len(),
+            config.target
+        );
+
+        if round + 1 < config.rounds {
+            sleep(config.interval).await;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, process};
+
+    #[test]
+    fn skips_rows_outside_the_register_block() {
+        let path = env::temp_dir().join(format!("iaq_map_gen_{}.csv", process::id()));
+        fs::write(
+            &path,
+            "version,register,bit,iaq_type,unit,data_type,divide_by\n\
+             0,0,,temperature,°C,INT16,10\n\
+             0,63,,co2,ppm,UINT32,1\n\
+             0,200,,pm25,µg/m³,UINT16,1\n",
+        )
+        .unwrap();
+        let table = IaqRegisterMapTable::from_path(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let regs = iaq_registers(&table, &mut XorShift64::new(1));
+        assert_eq!(regs.len(), IAQ_REGISTER_COUNT);
+        let temperature = regs[0] as i16 as f64 / 10.0;
+        assert!((18.0..=28.0).contains(&temperature), "{}", temperature);
+        assert_eq!(regs[63], 0);
+    }
+}
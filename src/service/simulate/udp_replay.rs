@@ -0,0 +1,255 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, TimeZone, Utc};
+use std::{fs, net::SocketAddr};
+use tokio::{
+    net::UdpSocket,
+    time::{Duration, sleep},
+};
+use tracing::info;
+
+use crate::service::utils::frame_log::{FrameLogEntry, read_frame_log};
+
+#[derive(Debug, Clone)]
+pub struct UdpReplayConfig {
+    pub target: SocketAddr,
+    pub file: String,
+    // 1.0 = 실시간, 10.0 = 10배속, 0 = 간격 없이
+    pub speed: f64,
+    // pcap 에서 이 목적지 포트의 UDP 만 재전송
+    pub port: Option<u16>,
+}
+
+impl UdpReplayConfig {
+    // udp-replay --file <frames.log|capture.pcap> [--target 127.0.0.1:5005] [--speed 1.0] [--port 5005]
+    pub fn from_args(args: &[String]) -> Result<UdpReplayConfig> {
+        let mut target: SocketAddr = "127.0.0.1:5005".parse()?;
+        let mut file = None;
+        let mut speed = 1.0;
+        let mut port = None;
+
+        let mut it = args.iter();
+        while let Some(flag) = it.next() {
+            let mut value = || {
+                it.next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", flag))
+            };
+            match flag.as_str() {
+                "--target" => target = value()?.parse()?,
+                "--file" => file = Some(value()?.clone()),
+                "--speed" => speed = value()?.parse()?,
+                "--port" => port = Some(value()?.parse()?),
+                other => return Err(anyhow!("Unknown udp-replay option: {}", other)),
+            }
+        }
+
+        Ok(UdpReplayConfig {
+            target,
+            file: file.ok_or_else(|| anyhow!("udp-replay requires --file"))?,
+            speed,
+            port,
+        })
+    }
+}
+
+const PCAP_MAGIC_US: u32 = 0xa1b2c3d4;
+const PCAP_MAGIC_NS: u32 = 0xa1b23c4d;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+
+// 고전 pcap (libpcap) 에서 IPv4/UDP 페이로드만 추출
+pub fn read_pcap(bytes: &[u8], port: Option<u16>) -> Result<Vec<FrameLogEntry>> {
+    if bytes.len() < 24 {
+        return Err(anyhow!("pcap file too short"));
+    }
+    let magic_le = u32::from_le_bytes(bytes[0..4].try_into()?);
+    let magic_be = u32::from_be_bytes(bytes[0..4].try_into()?);
+    let (le, nanos) = match (magic_le, magic_be) {
+        (PCAP_MAGIC_US, _) => (true, false),
+        (PCAP_MAGIC_NS, _) => (true, true),
+        (_, PCAP_MAGIC_US) => (false, false),
+        (_, PCAP_MAGIC_NS) => (false, true),
+        _ => return Err(anyhow!("Not a pcap file (pcapng is not supported)")),
+    };
+    let u32_at = |b: &[u8], i: usize| -> u32 {
+        let a = [b[i], b[i + 1], b[i + 2], b[i + 3]];
+        if le { u32::from_le_bytes(a) } else { u32::from_be_bytes(a) }
+    };
+    let linktype = u32_at(bytes, 20);
+
+    let mut entries = Vec::new();
+    let mut pos = 24;
+    while pos + 16 <= bytes.len() {
+        let ts_sec = u32_at(bytes, pos) as i64;
+        let ts_frac = u32_at(bytes, pos + 4);
+        let incl_len = u32_at(bytes, pos + 8) as usize;
+        pos += 16;
+        let Some(packet) = pos.checked_add(incl_len).and_then(|end| bytes.get(pos..end)) else {
+            break;
+        };
+        pos += incl_len;
+
+        let ip = match linktype {
+            LINKTYPE_ETHERNET => ethernet_payload(packet),
+            LINKTYPE_LINUX_SLL => packet.get(16..),
+            LINKTYPE_RAW => Some(packet),
+            other => return Err(anyhow!("Unsupported pcap link type {}", other)),
+        };
+        let Some((src, dst_port, payload)) = ip.and_then(udp_payload) else {
+            continue;
+        };
+        if port.is_some_and(|p| p != dst_port) {
+            continue;
+        }
+
+        // 마이크로초 필드가 깨져 있으면 수신 시각 대신 현재 시각
+        let nanos_part = if nanos { Some(ts_frac) } else { ts_frac.checked_mul(1000) };
+        let at: DateTime<Utc> = nanos_part
+            .and_then(|n| Utc.timestamp_opt(ts_sec, n).single())
+            .unwrap_or_else(Utc::now);
+        entries.push(FrameLogEntry {
+            at,
+            source: src,
+            bytes: payload.to_vec(),
+        });
+    }
+    Ok(entries)
+}
+
+fn ethernet_payload(packet: &[u8]) -> Option<&[u8]> {
+    let mut offset = 12;
+    let mut ethertype = u16::from_be_bytes([*packet.get(offset)?, *packet.get(offset + 1)?]);
+    // 802.1Q VLAN 태그
+    while ethertype == 0x8100 {
+        offset += 4;
+        ethertype = u16::from_be_bytes([*packet.get(offset)?, *packet.get(offset + 1)?]);
+    }
+    if ethertype != 0x0800 {
+        return None;
+    }
+    packet.get(offset + 2..)
+}
+
+// IPv4 → (출발지 주소, 목적지 포트, UDP 페이로드)
+fn udp_payload(ip: &[u8]) -> Option<(String, u16, &[u8])> {
+    if ip.first()? >> 4 != 4 || *ip.get(9)? != 17 {
+        return None;
+    }
+    let ihl = ((ip[0] & 0x0F) as usize) * 4;
+    if ihl < 20 {
+        return None;
+    }
+    let src = ip.get(12..16)?;
+    let src = format!("{}.{}.{}.{}", src[0], src[1], src[2], src[3]);
+    let udp = ip.get(ihl..)?;
+    let src_port = u16::from_be_bytes([*udp.first()?, *udp.get(1)?]);
+    let dst_port = u16::from_be_bytes([*udp.get(2)?, *udp.get(3)?]);
+    let udp_len = u16::from_be_bytes([*udp.get(4)?, *udp.get(5)?]) as usize;
+    let payload = udp.get(8..udp_len.min(udp.len()))?;
+    Some((format!("{}:{}", src, src_port), dst_port, payload))
+}
+
+// 기록된 프레임을 원래 간격(/speed)으로 리스너에 재전송
+pub async fn run_udp_replay(config: UdpReplayConfig) -> Result<()> {
+    let raw = fs::read(&config.file).with_context(|| format!("Could not read {}", config.file))?;
+    let is_pcap = raw.len() >= 4
+        && [PCAP_MAGIC_US, PCAP_MAGIC_NS].iter().any(|m| {
+            m.to_le_bytes() == raw[0..4] || m.to_be_bytes() == raw[0..4]
+        });
+    let entries = if is_pcap {
+        read_pcap(&raw, config.port)?
+    } else {
+        read_frame_log(&config.file)?
+    };
+    if entries.is_empty() {
+        return Err(anyhow!("No frames found in {}", config.file));
+    }
+
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    let mut prev = entries[0].at;
+    for entry in &entries {
+        if config.speed > 0.0 {
+            let gap = (entry.at - prev).to_std().unwrap_or(Duration::ZERO);
+            sleep(gap.div_f64(config.speed)).await;
+        }
+        prev = entry.at;
+        socket.send_to(&entry.bytes, config.target).await?;
+    }
+
+    info!(
+        "Replayed {} frames from {} to {}",
+        entries.len(),
+        config.file,
+        config.target
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 리틀엔디안 마이크로초 pcap, LINKTYPE_RAW
+    fn pcap(records: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&PCAP_MAGIC_US.to_le_bytes());
+        out.extend_from_slice(&[2, 0, 4, 0]);
+        out.extend_from_slice(&[0; 8]);
+        out.extend_from_slice(&65535u32.to_le_bytes());
+        out.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+        for (usec, packet) in records {
+            out.extend_from_slice(&1_760_000_000u32.to_le_bytes());
+            out.extend_from_slice(&usec.to_le_bytes());
+            out.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+            out.extend_from_slice(packet);
+        }
+        out
+    }
+
+    fn ipv4_udp(dst_port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut ip = vec![
+            0x45, 0, 0, 0, 0, 0, 0, 0, 64, 17, 0, 0, 10, 1, 0, 7, 10, 1, 0, 1,
+        ];
+        ip.extend_from_slice(&40000u16.to_be_bytes());
+        ip.extend_from_slice(&dst_port.to_be_bytes());
+        ip.extend_from_slice(&(8 + payload.len() as u16).to_be_bytes());
+        ip.extend_from_slice(&[0, 0]);
+        ip.extend_from_slice(payload);
+        ip
+    }
+
+    #[test]
+    fn extracts_udp_payloads() {
+        let bytes = pcap(&[
+            (250_000, ipv4_udp(5005, &[1, 2, 3])),
+            (0, ipv4_udp(6000, &[9])),
+        ]);
+        let entries = read_pcap(&bytes, Some(5005)).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].source, "10.1.0.7:40000");
+        assert_eq!(entries[0].bytes, [1, 2, 3]);
+        assert_eq!(entries[0].at.timestamp_subsec_micros(), 250_000);
+    }
+
+    #[test]
+    fn malformed_packets_do_not_panic() {
+        let mut short_ihl = ipv4_udp(5005, &[1]);
+        short_ihl[0] = 0x41;
+        let bytes = pcap(&[
+            (0, vec![0x45, 0, 0, 0, 0, 0, 0, 0, 64, 17]), // 주소 전에 잘림
+            (0, short_ihl),
+            (u32::MAX, ipv4_udp(5005, &[7])), // 마이크로초 필드 overflow
+        ]);
+        let entries = read_pcap(&bytes, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].bytes, [7]);
+    }
+
+    #[test]
+    fn truncated_record_stops_reading() {
+        let mut bytes = pcap(&[(0, ipv4_udp(5005, &[1, 2, 3]))]);
+        bytes.truncate(bytes.len() - 1);
+        assert!(read_pcap(&bytes, None).unwrap().is_empty());
+    }
+}
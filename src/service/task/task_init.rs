@@ -1,11 +1,11 @@
 use anyhow::Result;
 use std::sync::Arc;
-use tokio::time::{Duration, timeout};
 use tracing::{error, info};
 
 use crate::service::{
+    gateway::modbus_gateway::run_modbus_gateway,
     collect::{
-        gas::handle_gas_data, gems_3500_modbus::collection_gems_3500_modbus, heat::handle_heat_data,
+        gems_3500_modbus::collection_gems_3500_modbus, virtual_points::handle_virtual_points,
     },
     server::{get_state::ServerState, udp_listener::run_udp_listener},
     task::common_scheduling::{SECONDS_1MINUTE, SECONDS_5MINUTE, schedule_task},
@@ -14,6 +14,16 @@ use crate::service::{
 pub async fn task_init(state: Arc<ServerState>) -> Result<()> {
     info!("Task scheduler running...");
 
+    // 최신값 캐시를 Modbus TCP 로 노출 (레지스터 맵이 있을 때만)
+    {
+        let coroutine_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(e) = run_modbus_gateway(coroutine_state).await {
+                error!("Modbus gateway stopped: {:?}", e);
+            }
+        });
+    }
+
     {
         let coroutine_state = Arc::clone(&state);
         tokio::spawn(async move {
@@ -35,26 +45,49 @@ pub async fn task_init(state: Arc<ServerState>) -> Result<()> {
         });
     }
 
-    // {
-    //     let coroutine_state = Arc::clone(&state);
-    //     tokio::spawn(async move {
-    //         schedule_task(
-    //             Arc::clone(&coroutine_state),
-    //             move |st| async move {
-    //                 match run_udp_listener(st).await {
-    //                     Ok(_) => (),
-    //                     Err(e) => {
-    //                         error!("Could not collect iaq data: {:?}", e);
-    //                     }
-    //                 }
-    //             },
-    //             String::from("collect iaq data from client server"),
-    //             SECONDS_5MINUTE,
-    //             0,
-    //         )
-    //             .await
-    //     });
-    // }
+    // 가상 포인트: 매분 수집이 끝난 뒤(30초 지연) 최신값으로 계산
+    {
+        let coroutine_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            schedule_task(
+                Arc::clone(&coroutine_state),
+                move |st| async move {
+                    match handle_virtual_points(st).await {
+                        Ok(_) => (),
+                        Err(e) => {
+                            error!("Could not evaluate virtual points: {:?}", e);
+                        }
+                    }
+                },
+                String::from("evaluate virtual measurement points"),
+                SECONDS_1MINUTE,
+                30,
+            )
+                .await
+        });
+    }
+
+    // UDP 수신 (IAQ / CCM / SIHAS): 5분마다 30초 동안 받아 포인트별 최신 레코드를 전송
+    {
+        let coroutine_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            schedule_task(
+                Arc::clone(&coroutine_state),
+                move |st| async move {
+                    match run_udp_listener(st).await {
+                        Ok(_) => (),
+                        Err(e) => {
+                            error!("Could not collect UDP sensor data: {:?}", e);
+                        }
+                    }
+                },
+                String::from("collect udp sensor data (iaq / ccm / sihas)"),
+                SECONDS_5MINUTE,
+                0,
+            )
+                .await
+        });
+    }
 
     // {
     //     let coroutine_state = Arc::clone(&state);
@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+
+// 시스템 시계 동기화 상태 (adjtimex). 타임스탬프를 믿을 수 있는지 판단용
+#[derive(Serialize, Debug)]
+pub struct ClockHealth {
+    pub synchronized: Option<bool>, // 알 수 없으면 None
+    pub clock_state: String,
+    pub offset_us: Option<f64>,
+    pub max_error_us: Option<i64>,
+    pub est_error_us: Option<i64>,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn clock_health() -> ClockHealth {
+    // modes = 0 이면 읽기만 한다
+    let mut tx: libc::timex = unsafe { std::mem::zeroed() };
+    let state = unsafe { libc::adjtimex(&mut tx) };
+    let checked_at = Utc::now();
+
+    if state < 0 {
+        return ClockHealth {
+            synchronized: None,
+            clock_state: format!("error: {}", std::io::Error::last_os_error()),
+            offset_us: None,
+            max_error_us: None,
+            est_error_us: None,
+            checked_at,
+        };
+    }
+
+    let clock_state = match state {
+        libc::TIME_OK => "ok",
+        libc::TIME_INS => "insert_leap_second",
+        libc::TIME_DEL => "delete_leap_second",
+        libc::TIME_OOP => "leap_second_in_progress",
+        libc::TIME_WAIT => "leap_second_done",
+        libc::TIME_ERROR => "unsynchronized",
+        _ => "unknown",
+    };
+    let offset_us = if tx.status & libc::STA_NANO != 0 {
+        tx.offset as f64 / 1000.0
+    } else {
+        tx.offset as f64
+    };
+
+    ClockHealth {
+        synchronized: Some(state != libc::TIME_ERROR && tx.status & libc::STA_UNSYNC == 0),
+        clock_state: clock_state.to_owned(),
+        offset_us: Some(offset_us),
+        max_error_us: Some(tx.maxerror as i64),
+        est_error_us: Some(tx.esterror as i64),
+        checked_at,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn clock_health() -> ClockHealth {
+    ClockHealth {
+        synchronized: None,
+        clock_state: "unsupported".to_owned(),
+        offset_us: None,
+        max_error_us: None,
+        est_error_us: None,
+        checked_at: Utc::now(),
+    }
+}
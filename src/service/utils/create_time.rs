@@ -4,38 +4,57 @@ pub const HOUR: &str = "HOUR";
 pub const DAY: &str = "DAY";
 
 pub fn utc_now_minute() -> DateTime<Utc> {
-    let now = Utc::now();
-
-    now.with_second(0)
-        .and_then(|dt| dt.with_nanosecond(0))
-        .expect("Fail second, nanosecond Set 0.")
+    truncate_to(Utc::now(), MINUTE)
 }
 
 pub fn utc_now_hour() -> DateTime<Utc> {
-    let now = Utc::now();
-
-    now.with_minute(0)
-        .and_then(|dt| dt.with_second(0))
-        .and_then(|dt| dt.with_nanosecond(0))
-        .expect("Fail second, nanosecond Set 0.")
+    truncate_to(Utc::now(), HOUR)
 }
 
 pub fn utc_now_day() -> DateTime<Utc> {
-    let now = Utc::now();
+    truncate_to(Utc::now(), DAY)
+}
 
-    now.with_hour(0)
-        .and_then(|dt| dt.with_minute(0))
-        .and_then(|dt| dt.with_second(0))
+// 주어진 시각을 단위(MINUTE/HOUR/DAY) 시작으로 내림
+pub fn truncate_to(date_time: DateTime<Utc>, time_unit: &str) -> DateTime<Utc> {
+    let minute = date_time
+        .with_second(0)
         .and_then(|dt| dt.with_nanosecond(0))
-        .expect("Fail second, nanosecond Set 0.")
+        .expect("Fail second, nanosecond Set 0.");
+
+    match time_unit {
+        DAY => minute
+            .with_hour(0)
+            .and_then(|dt| dt.with_minute(0))
+            .expect("Fail hour, minute Set 0."),
+        HOUR => minute.with_minute(0).expect("Fail minute Set 0."),
+        _ => minute,
+    }
+}
+
+pub fn unit_minutes(time_unit: &str) -> i64 {
+    match time_unit {
+        DAY => 24 * 60,
+        HOUR => 60,
+        _ => 1,
+    }
 }
 
-pub fn utc_now_ago(ago_seconds: i64, time_unit: &str) -> DateTime<Utc> {
-    let date_time = match time_unit {
-        DAY => utc_now_day(),
-        HOUR => utc_now_hour(),
-        _ => utc_now_minute(),
-    };
+// date_time 이 속한 구간 [시작, 끝). 구간은 단위 시작부터 window_minutes 씩
+pub fn window_bounds(
+    date_time: DateTime<Utc>,
+    window_minutes: i64,
+    time_unit: &str,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let base = truncate_to(date_time, time_unit);
+    let offset = (date_time - base).num_minutes() / window_minutes * window_minutes;
+    let start = base + Duration::minutes(offset);
+
+    (start, start + Duration::minutes(window_minutes))
+}
 
-    date_time - Duration::seconds(ago_seconds)
+// 주기(초) 단위로 내린 예정 수집 시각. 태스크가 조금 늦게 돌아도 원래 슬롯을 가리킨다
+pub fn slot_start(date_time: DateTime<Utc>, cycle_seconds: i64) -> DateTime<Utc> {
+    let ts = date_time.timestamp() / cycle_seconds * cycle_seconds;
+    DateTime::from_timestamp(ts, 0).expect("Fail slot timestamp.")
 }
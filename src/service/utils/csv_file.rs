@@ -0,0 +1,18 @@
+use anyhow::Result;
+use std::{fs::File, io};
+
+// 선택 설정 CSV 열기. 파일이 없으면 None (호출 측에서 빈 설정으로 처리)
+pub fn open_optional_csv(path: &str) -> Result<Option<csv::Reader<File>>> {
+    match csv::Reader::from_path(path) {
+        Ok(rdr) => Ok(Some(rdr)),
+        Err(e) => {
+            if let csv::ErrorKind::Io(io_err) = e.kind()
+                && io_err.kind() == io::ErrorKind::NotFound
+            {
+                return Ok(None);
+            }
+
+            Err(e.into())
+        }
+    }
+}
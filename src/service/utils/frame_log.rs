@@ -0,0 +1,110 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+// 원시 프레임 기록 형식 (한 줄에 한 프레임):
+//   <RFC3339 수신시각>\t<출처/방향>\t<16진수 바이트>
+// 캡처 파일과 재전송 도구가 같은 형식을 사용한다.
+#[derive(Debug, Clone)]
+pub struct FrameLogEntry {
+    pub at: DateTime<Utc>,
+    pub source: String,
+    pub bytes: Vec<u8>,
+}
+
+impl FrameLogEntry {
+    pub fn to_line(&self) -> String {
+        format!("{}\t{}\t{}", self.at.to_rfc3339(), self.source, to_hex(&self.bytes))
+    }
+
+    pub fn from_line(line: &str) -> Result<FrameLogEntry> {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(at), Some(source), Some(hex)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(anyhow!("Malformed frame log line: {}", line));
+        };
+        Ok(FrameLogEntry {
+            at: DateTime::parse_from_rfc3339(at)
+                .with_context(|| format!("Invalid timestamp in frame log: {}", at))?
+                .with_timezone(&Utc),
+            source: source.to_string(),
+            bytes: from_hex(hex.trim())?,
+        })
+    }
+}
+
+pub fn read_frame_log(path: &str) -> Result<Vec<FrameLogEntry>> {
+    let file = File::open(path).with_context(|| format!("Could not open frame log {}", path))?;
+    let mut entries = Vec::new();
+    for (no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        entries.push(
+            FrameLogEntry::from_line(&line).with_context(|| format!("{}:{}", path, no + 1))?,
+        );
+    }
+    Ok(entries)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 바이트 단위로 읽는다 (멀티바이트 문자가 섞여도 패닉 없이 에러)
+pub fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    let bytes = hex.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return Err(anyhow!("Odd-length hex string"));
+    }
+    bytes
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| match (hex_digit(pair[0]), hex_digit(pair[1])) {
+            (Some(hi), Some(lo)) => Ok(hi << 4 | lo),
+            _ => Err(anyhow!("Invalid hex at {}", i * 2)),
+        })
+        .collect()
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        let bytes = [0x00, 0x1a, 0xff, 0x80];
+        assert_eq!(from_hex(&to_hex(&bytes)).unwrap(), bytes);
+        assert_eq!(from_hex("00FFaB").unwrap(), [0x00, 0xff, 0xab]);
+    }
+
+    #[test]
+    fn rejects_bad_hex_without_panicking() {
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("0g").is_err());
+        assert!(from_hex("+1").is_err());
+        // 2바이트 문자: 문자 경계가 아닌 곳을 자르면 패닉하던 입력
+        assert!(from_hex("aé").is_err());
+        assert!(from_hex("éé").is_err());
+    }
+
+    #[test]
+    fn parses_log_line() {
+        let entry =
+            FrameLogEntry::from_line("2026-10-19T01:12:04+00:00\t10.1.0.77:5005\t0102").unwrap();
+        assert_eq!(entry.source, "10.1.0.77:5005");
+        assert_eq!(entry.bytes, [1, 2]);
+        assert_eq!(
+            FrameLogEntry::from_line(&entry.to_line()).unwrap().bytes,
+            [1, 2]
+        );
+        assert!(FrameLogEntry::from_line("2026-10-19T01:12:04+00:00\t0102").is_err());
+    }
+}